@@ -0,0 +1,286 @@
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rand::prelude::*;
+use serde::Serialize;
+
+use crate::config::{Experiment, InstanceEntry, ParameterOverrides};
+use crate::genetic::{prd, GeneticError, GeneticRng};
+use crate::instance::Instance;
+use crate::parameters::Parameters;
+use crate::problem::PermutationProblem;
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRecord {
+    pub instance: String,
+    pub dimension: usize,
+    pub configuration: String,
+    pub parameters: Parameters,
+    pub repetition: usize,
+    pub seed: u64,
+    pub length: u32,
+    pub prd: f64,
+    pub elapsed_secs: f64,
+    pub generations: usize,
+}
+
+pub struct ExperimentRunner {
+    instances: Vec<InstanceEntry>,
+    configurations: Vec<(String, ParameterOverrides)>,
+    repetitions: usize,
+    parallel_instances: usize,
+    seed: Option<u64>,
+}
+
+impl ExperimentRunner {
+    pub fn new(
+        instances: Vec<InstanceEntry>,
+        configurations: Vec<(String, ParameterOverrides)>,
+        repetitions: usize,
+    ) -> Self {
+        ExperimentRunner {
+            instances,
+            configurations,
+            repetitions,
+            parallel_instances: 1,
+            seed: None,
+        }
+    }
+
+    pub fn from_experiment(experiment: &Experiment) -> Self {
+        let runner = ExperimentRunner::new(
+            experiment.instances.clone(),
+            experiment.configurations(),
+            experiment.repetitions,
+        )
+        .with_parallel_instances(experiment.parallel_instances);
+
+        match experiment.seed {
+            Some(seed) => runner.with_seed(seed),
+            None => runner,
+        }
+    }
+
+    pub fn with_parallel_instances(mut self, parallel_instances: usize) -> Self {
+        self.parallel_instances = parallel_instances.max(1);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn run(&self) -> Result<Vec<RunRecord>, GeneticError> {
+        let mut rng = match self.seed {
+            Some(seed) => GeneticRng::seed_from_u64(seed),
+            None => GeneticRng::from_entropy(),
+        };
+
+        let instance_seeds = self
+            .instances
+            .iter()
+            .map(|_| rng.gen::<u64>())
+            .collect::<Vec<_>>();
+
+        let next_instance = AtomicUsize::new(0);
+
+        let results = std::thread::scope(|scope| {
+            let handles = (0..self.parallel_instances.min(self.instances.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut records = vec![];
+
+                        loop {
+                            let i = next_instance.fetch_add(1, Ordering::Relaxed);
+
+                            if i >= self.instances.len() {
+                                return Ok(records);
+                            }
+
+                            records.push((
+                                i,
+                                self.run_instance(&self.instances[i], instance_seeds[i])?,
+                            ));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().map_err(|_| GeneticError::WorkerPanicked)?)
+                .collect::<Result<Vec<Vec<_>>, GeneticError>>()
+        })?;
+
+        let mut results = results.into_iter().flatten().collect::<Vec<_>>();
+
+        results.sort_by_key(|(i, _)| *i);
+
+        Ok(results
+            .into_iter()
+            .flat_map(|(_, records)| records)
+            .collect())
+    }
+
+    fn run_instance(
+        &self,
+        entry: &InstanceEntry,
+        seed: u64,
+    ) -> Result<Vec<RunRecord>, GeneticError> {
+        let instance = load_instance(&entry.path)?;
+        let name = instance_name(&entry.path);
+
+        let mut rng = GeneticRng::seed_from_u64(seed);
+        let mut records = Vec::with_capacity(self.configurations.len() * self.repetitions);
+
+        for (label, overrides) in &self.configurations {
+            let parameters = overrides.resolve(&instance);
+
+            for repetition in 0..self.repetitions {
+                let seed = rng.gen::<u64>();
+
+                let (route, stats) = parameters
+                    .to_genetic(entry.opt)
+                    .with_seed(seed)
+                    .try_solve_with_stats(&instance)?;
+
+                let length = instance
+                    .evaluate(&route)
+                    .ok_or(GeneticError::InvalidRoute)?;
+
+                records.push(RunRecord {
+                    instance: name.clone(),
+                    dimension: instance.dimension(),
+                    configuration: label.clone(),
+                    parameters,
+                    repetition,
+                    seed,
+                    length,
+                    prd: prd(length, entry.opt),
+                    elapsed_secs: stats.elapsed_secs,
+                    generations: stats.generations,
+                });
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+pub fn load_instance(path: &str) -> Result<Instance, GeneticError> {
+    TsplibFile::from_file(path)?.to_instance()
+}
+
+pub fn instance_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map_or(String::new(), |stem| stem.to_string_lossy().to_string())
+}
+
+pub fn write_results_csv<W: Write>(writer: &mut W, records: &[RunRecord]) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "instance,dimension,configuration,iterations,population_size,elites_count,pair_count,\
+         crossing_prob,mutation_prob,stagnation_iter,max_mutation_multiply,memetic_fraction,\
+         crossover,mutation,self_adaptive,operator_selection,replacement,repetition,seed,length,\
+         prd,elapsed_secs,generations"
+    )?;
+
+    for record in records {
+        let parameters = &record.parameters;
+
+        writeln!(
+            writer,
+            "{},{},\"{}\",{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{:.4},{:.3},{}",
+            record.instance,
+            record.dimension,
+            record.configuration,
+            parameters.iterations,
+            parameters.population_size,
+            parameters.elites_count,
+            parameters.pair_count,
+            parameters.crossing_prob,
+            parameters.mutation_prob,
+            parameters.stagnation_iter,
+            parameters.max_mutation_multiply,
+            parameters.memetic_fraction,
+            parameters.crossover,
+            parameters.mutation,
+            parameters.self_adaptive,
+            parameters.operator_selection,
+            parameters.replacement,
+            record.repetition,
+            record.seed,
+            record.length,
+            record.prd,
+            record.elapsed_secs,
+            record.generations
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn experiment_runs_are_reproducible_and_tidy() {
+        let path = std::env::temp_dir()
+            .join(format!("genetyk-experiment-{}.tsp", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
+        let coordinates = (0..12)
+            .map(|i| format!("{} {} {}\n", i + 1, i * 37 % 101, i * 73 % 89))
+            .collect::<String>();
+
+        std::fs::write(
+            &path,
+            format!(
+                "NAME : small\nDIMENSION : 12\nEDGE_WEIGHT_TYPE : EUC_2D\nNODE_COORD_SECTION\n{}EOF\n",
+                coordinates
+            ),
+        )
+        .unwrap();
+
+        let experiment = Experiment::from_toml(&format!(
+            "name = \"small\"\nrepetitions = 2\nseed = 5\nparallel_instances = 2\n\
+             instances = [{{ path = \"{0}\", opt = 300 }}, {{ path = \"{0}\", opt = 300 }}]\n\
+             [parameters]\niterations = 10\npopulation_size = 20\nthreads = 1\n\
+             [grid]\ncrossover = [\"pmx\", \"order\"]\n",
+            path
+        ))
+        .unwrap();
+
+        let records = ExperimentRunner::from_experiment(&experiment)
+            .run()
+            .unwrap();
+        let repeated = ExperimentRunner::from_experiment(&experiment)
+            .run()
+            .unwrap();
+
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(8, records.len());
+        assert_eq!("crossover=order", records[2].configuration);
+
+        for (record, repeated) in records.iter().zip(&repeated) {
+            assert_eq!(record.seed, repeated.seed);
+            assert_eq!(record.length, repeated.length);
+        }
+
+        let mut csv = vec![];
+        write_results_csv(&mut csv, &records).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(23, lines.next().unwrap().split(',').count());
+        assert_eq!(8, lines.count());
+    }
+}
@@ -0,0 +1,218 @@
+use rand::prelude::*;
+use serde::Serialize;
+
+use crate::benchmarking::{ExperimentRunner, RunRecord};
+use crate::config::{Experiment, InstanceEntry, ParameterOverrides};
+use crate::genetic::{GeneticError, GeneticRng};
+
+const DEFAULT_ELIMINATION_FACTOR: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Standing {
+    pub configuration: String,
+    pub mean_prd: f64,
+    pub runs: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaceOutcome {
+    pub best: (String, ParameterOverrides),
+    pub rounds: Vec<Vec<Standing>>,
+}
+
+pub struct Race {
+    instances: Vec<InstanceEntry>,
+    configurations: Vec<(String, ParameterOverrides)>,
+    repetitions: usize,
+    elimination_factor: usize,
+    parallel_instances: usize,
+    seed: Option<u64>,
+}
+
+impl Race {
+    pub fn new(
+        instances: Vec<InstanceEntry>,
+        configurations: Vec<(String, ParameterOverrides)>,
+        repetitions: usize,
+    ) -> Self {
+        Race {
+            instances,
+            configurations,
+            repetitions: repetitions.max(1),
+            elimination_factor: DEFAULT_ELIMINATION_FACTOR,
+            parallel_instances: 1,
+            seed: None,
+        }
+    }
+
+    pub fn from_experiment(experiment: &Experiment) -> Self {
+        let race = Race::new(
+            experiment.instances.clone(),
+            experiment.configurations(),
+            experiment.repetitions,
+        )
+        .with_parallel_instances(experiment.parallel_instances);
+
+        match experiment.seed {
+            Some(seed) => race.with_seed(seed),
+            None => race,
+        }
+    }
+
+    pub fn with_elimination_factor(mut self, elimination_factor: usize) -> Self {
+        self.elimination_factor = elimination_factor.max(2);
+        self
+    }
+
+    pub fn with_parallel_instances(mut self, parallel_instances: usize) -> Self {
+        self.parallel_instances = parallel_instances.max(1);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn run(&self) -> Result<RaceOutcome, GeneticError> {
+        if self.configurations.is_empty() || self.instances.is_empty() {
+            return Err(GeneticError::InvalidConfiguration(
+                "racing needs at least one configuration and one instance",
+            ));
+        }
+
+        let mut rng = match self.seed {
+            Some(seed) => GeneticRng::seed_from_u64(seed),
+            None => GeneticRng::from_entropy(),
+        };
+
+        let mut survivors = self.configurations.clone();
+        let mut records: Vec<RunRecord> = vec![];
+        let mut rounds = vec![];
+        let mut repetitions = self.repetitions;
+
+        loop {
+            let round =
+                ExperimentRunner::new(self.instances.clone(), survivors.clone(), repetitions)
+                    .with_parallel_instances(self.parallel_instances)
+                    .with_seed(rng.gen())
+                    .run()?;
+
+            records.extend(round);
+
+            let standings = standings(&survivors, &records);
+
+            eprintln!("Round {}:", rounds.len() + 1);
+
+            for standing in &standings {
+                eprintln!(
+                    "  {} mean PRD {:.4} over {} runs",
+                    standing.configuration, standing.mean_prd, standing.runs
+                );
+            }
+
+            let keep = survivors.len().div_ceil(self.elimination_factor);
+
+            survivors = standings[..keep]
+                .iter()
+                .filter_map(|standing| {
+                    survivors
+                        .iter()
+                        .find(|(label, _)| *label == standing.configuration)
+                        .cloned()
+                })
+                .collect();
+
+            rounds.push(standings);
+
+            if survivors.len() == 1 {
+                break;
+            }
+
+            repetitions *= self.elimination_factor;
+        }
+
+        Ok(RaceOutcome {
+            best: survivors.remove(0),
+            rounds,
+        })
+    }
+}
+
+fn standings(
+    configurations: &[(String, ParameterOverrides)],
+    records: &[RunRecord],
+) -> Vec<Standing> {
+    let mut standings = configurations
+        .iter()
+        .map(|(label, _)| {
+            let prds = records
+                .iter()
+                .filter(|record| record.configuration == *label)
+                .map(|record| record.prd)
+                .collect::<Vec<_>>();
+
+            Standing {
+                configuration: label.clone(),
+                mean_prd: prds.iter().sum::<f64>() / prds.len().max(1) as f64,
+                runs: prds.len(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    standings.sort_by(|first, second| first.mean_prd.total_cmp(&second.mean_prd));
+
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::generator::{Generator, Layout};
+
+    #[test]
+    fn race_halves_configurations_until_one_is_left() {
+        let path = std::env::temp_dir()
+            .join(format!("genetyk-race-{}.tsp", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
+        Generator::new(Layout::Uniform, 12, 4)
+            .generate()
+            .write_tsplib(&path, "race")
+            .unwrap();
+
+        let experiment = Experiment::from_toml(&format!(
+            "name = \"race\"\nrepetitions = 1\nseed = 9\n\
+             instances = [{{ path = \"{}\", opt = 1 }}]\n\
+             [parameters]\niterations = 10\npopulation_size = 20\nthreads = 1\n\
+             [grid]\nmutation_prob = [0.0, 0.01, 0.05]\ncrossover = [\"pmx\", \"order\"]\n",
+            path
+        ))
+        .unwrap();
+
+        let outcome = Race::from_experiment(&experiment).run().unwrap();
+        let repeated = Race::from_experiment(&experiment).run().unwrap();
+
+        std::fs::remove_file(&path).ok();
+
+        let survivors = outcome
+            .rounds
+            .iter()
+            .map(|round| round.len())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![6, 3, 2], survivors);
+        assert_eq!(
+            vec![1, 3, 7],
+            outcome
+                .rounds
+                .iter()
+                .map(|round| round[0].runs)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(outcome.rounds[2][0].configuration, outcome.best.0);
+        assert_eq!(outcome, repeated);
+    }
+}
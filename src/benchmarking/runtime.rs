@@ -1,26 +1,57 @@
 use std::io::Write;
+use std::time::{Duration, Instant};
 
-use tsp_parser::{run_heuristic_with_bench, HeuristicBench, Tsp, TspFileType, TspParser};
-
-use crate::genetic::Genetic;
+use crate::generator::{Generator, Layout};
+use crate::genetic::{Genetic, GeneticError};
+use crate::instance::Instance;
+use crate::problem::PermutationProblem;
 
 const NUM_PROBLEMS: usize = 10;
 
 pub fn bench_threads() {
+    let mut largest_durations = vec![];
+
     for threads in 1..=5 {
         let mut file = std::fs::File::create(format!("bench_results/threads_{}", threads))
             .expect("Couldnt create bench file");
 
+        let mut duration = 0.0;
+
         for problem_size in 10..=100 {
             if problem_size % 2 == 0 {
-                let tsps = generate_tsps_for_runtime(problem_size, TspFileType::LowerDiagRow);
+                let instances = generate_instances_for_runtime(problem_size, Layout::Symmetric);
 
-                let duration = get_avg_duration(&tsps, threads);
+                duration = get_avg_duration(&instances, threads);
 
                 file.write(format!("{} {}\n", problem_size, duration).as_bytes())
                     .expect("couldn't write to file");
             }
         }
+
+        largest_durations.push(duration);
+    }
+
+    write_speedups(&largest_durations);
+}
+
+fn write_speedups(durations: &[f64]) {
+    let mut file =
+        std::fs::File::create("bench_results/threads_speedup").expect("Couldnt create bench file");
+
+    let cores = Genetic::default_threads();
+
+    for (i, duration) in durations.iter().enumerate() {
+        file.write_all(
+            format!(
+                "{} {} {:.2} {}\n",
+                i + 1,
+                duration,
+                durations[0] / duration,
+                cores
+            )
+            .as_bytes(),
+        )
+        .expect("couldn't write to file");
     }
 }
 
@@ -28,52 +59,123 @@ pub fn bench_runtime() {
     let mut file =
         std::fs::File::create("bench_results/runtimes").expect("Couldnt create bench file");
 
-    for n in 1..=100 {
-        let tsps = generate_tsps_for_runtime(n, TspFileType::Euc2d);
+    // With fewer than 4 cities there is only one tour, too few for a population of two.
+    for n in 4..=100 {
+        let instances = generate_instances_for_runtime(n, Layout::Uniform);
 
-        let duration = get_avg_duration(&tsps, 4);
+        let duration = get_avg_duration(&instances, 4);
 
         file.write(format!("{} {}\n", n, duration).as_bytes())
             .expect("couldn't write to file");
     }
 }
 
-fn get_avg_duration(tsps: &[Tsp], threads: usize) -> f64 {
-    let mut duration_sum = 0;
-
-    for tsp in tsps {
-        let HeuristicBench {
-            route: _,
-            route_len: _,
-            duration,
-        } = run_heuristic_with_bench(
-            &tsp,
-            Genetic::new(0, 1000, 100, 5, 50, 0.9, 0.02, 100, 4, threads, 0.1), // default params
-        );
-
-        duration_sum += duration.as_millis();
+pub fn bench_worker_pool() {
+    let mut file =
+        std::fs::File::create("bench_results/worker_pool").expect("Couldnt create bench file");
+
+    let instance = Generator::new(Layout::Uniform, 100, 1)
+        .instance()
+        .expect("couldn't generate instance");
+
+    let threads = 4;
+
+    for generations in (100..=1000).step_by(100) {
+        let started = Instant::now();
+
+        Genetic::new(0, generations, 100, 5, 50, 0.9, 0.02, 100, 4, threads, 0.1)
+            .with_seed(generations as u64)
+            .try_solve(&instance)
+            .expect("genetic algorithm failed");
+
+        let persistent_pool = started.elapsed();
+        let spawn_per_generation =
+            persistent_pool + spawn_overhead(&instance, generations, threads);
+
+        file.write_all(
+            format!(
+                "{} {} {}\n",
+                generations,
+                spawn_per_generation.as_millis(),
+                persistent_pool.as_millis()
+            )
+            .as_bytes(),
+        )
+        .expect("couldn't write to file");
     }
+}
 
-    let duration_avg = duration_sum as f64 / tsps.len() as f64;
+fn spawn_overhead(instance: &Instance, generations: usize, threads: usize) -> Duration {
+    let started = Instant::now();
 
-    duration_avg
+    for _ in 0..generations {
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                let instance = instance.clone();
+
+                scope.spawn(move || instance.dimension());
+            }
+        });
+    }
+
+    started.elapsed()
 }
 
-fn generate_tsps_for_runtime(size: usize, file_type: TspFileType) -> Vec<Tsp> {
-    let tsp_type = match file_type {
-        TspFileType::Euc2d => "euc_2d",
-        TspFileType::LowerDiagRow => "lower_diag_row",
-        TspFileType::FullMatrix => "full_matrix",
-    };
+fn get_avg_duration(instances: &[Instance], threads: usize) -> f64 {
+    let mut duration_sum = 0;
+
+    for instance in instances {
+        duration_sum += time_solve(instance, threads).as_millis();
+    }
 
-    let mut tsps = Vec::with_capacity(NUM_PROBLEMS);
+    let duration_avg = duration_sum as f64 / instances.len() as f64;
 
-    for i in 1..=NUM_PROBLEMS {
-        let tsp = TspParser::from_file(&format!("new_problems/{}-v{}-{}.tsp", tsp_type, i, size))
-            .expect("File doesnt exist");
+    duration_avg
+}
 
-        tsps.push(tsp);
+fn time_solve(instance: &Instance, threads: usize) -> Duration {
+    // Small instances have fewer distinct tour lengths than the default population of 100.
+    let mut population_size = distinct_tours(instance.dimension()).min(100);
+
+    loop {
+        let elites_count = population_size.min(5);
+
+        let genetic = Genetic::new(
+            0,
+            1000,
+            population_size,
+            elites_count,
+            50,
+            0.9,
+            0.02,
+            100,
+            4,
+            threads,
+            0.1,
+        ); // default params
+
+        let started = Instant::now();
+
+        match genetic.try_solve(instance) {
+            Ok(_) => return started.elapsed(),
+            Err(GeneticError::NotEnoughDistinctRoutes(_)) if population_size > 2 => {
+                population_size = (population_size / 2).max(2);
+            }
+            Err(err) => panic!("genetic algorithm failed: {}", err),
+        }
     }
+}
+
+fn distinct_tours(dimension: usize) -> usize {
+    (3..dimension).fold(1, |tours, cities| tours.saturating_mul(cities))
+}
 
-    tsps
+fn generate_instances_for_runtime(size: usize, layout: Layout) -> Vec<Instance> {
+    (1..=NUM_PROBLEMS)
+        .map(|i| {
+            Generator::new(layout, size, i as u64)
+                .instance()
+                .expect("couldn't generate instance")
+        })
+        .collect()
 }
@@ -0,0 +1,370 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::benchmarking::RunRecord;
+
+const T_975: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Summary {
+    pub runs: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+impl Summary {
+    pub fn new(values: &[f64]) -> Option<Summary> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let runs = values.len();
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let mean = values.iter().sum::<f64>() / runs as f64;
+
+        let median = if runs.is_multiple_of(2) {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2.0
+        } else {
+            sorted[runs / 2]
+        };
+
+        let stddev = if runs > 1 {
+            let squares = values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>();
+
+            (squares / (runs - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        let half_width = t_quantile(runs.saturating_sub(1)) * stddev / (runs as f64).sqrt();
+
+        Some(Summary {
+            runs,
+            mean,
+            median,
+            stddev,
+            min: sorted[0],
+            max: sorted[runs - 1],
+            ci_low: mean - half_width,
+            ci_high: mean + half_width,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigurationSummary {
+    pub instance: String,
+    pub configuration: String,
+    pub prd: Summary,
+    pub best_length: u32,
+    pub success_rate: f64,
+    pub mean_elapsed_secs: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub z: f64,
+    pub p_value: f64,
+}
+
+pub fn summarize(records: &[RunRecord]) -> Vec<ConfigurationSummary> {
+    let mut groups: Vec<(&str, &str, Vec<&RunRecord>)> = vec![];
+
+    for record in records {
+        let group = groups.iter_mut().find(|(instance, configuration, _)| {
+            *instance == record.instance && *configuration == record.configuration
+        });
+
+        match group {
+            Some((_, _, group)) => group.push(record),
+            None => groups.push((&record.instance, &record.configuration, vec![record])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter_map(|(instance, configuration, group)| {
+            let prds = group.iter().map(|record| record.prd).collect::<Vec<_>>();
+            let successes = group.iter().filter(|record| record.prd <= 0.0).count();
+
+            Some(ConfigurationSummary {
+                instance: instance.to_string(),
+                configuration: configuration.to_string(),
+                prd: Summary::new(&prds)?,
+                best_length: group.iter().map(|record| record.length).min()?,
+                success_rate: successes as f64 / group.len() as f64,
+                mean_elapsed_secs: group.iter().map(|record| record.elapsed_secs).sum::<f64>()
+                    / group.len() as f64,
+            })
+        })
+        .collect()
+}
+
+pub fn write_summary_csv<W: Write>(
+    writer: &mut W,
+    summaries: &[ConfigurationSummary],
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "instance,configuration,runs,mean_prd,median_prd,stddev_prd,min_prd,max_prd,\
+         ci_low,ci_high,best_length,success_rate,mean_elapsed_secs"
+    )?;
+
+    for summary in summaries {
+        let prd = &summary.prd;
+
+        writeln!(
+            writer,
+            "{},\"{}\",{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{},{:.2},{:.3}",
+            summary.instance,
+            summary.configuration,
+            prd.runs,
+            prd.mean,
+            prd.median,
+            prd.stddev,
+            prd.min,
+            prd.max,
+            prd.ci_low,
+            prd.ci_high,
+            summary.best_length,
+            summary.success_rate,
+            summary.mean_elapsed_secs
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn paired_instance_means(
+    summaries: &[ConfigurationSummary],
+    first_configuration: &str,
+    second_configuration: &str,
+) -> (Vec<f64>, Vec<f64>) {
+    let mut first = vec![];
+    let mut second = vec![];
+
+    for summary in summaries {
+        if summary.configuration != first_configuration {
+            continue;
+        }
+
+        let other = summaries.iter().find(|other| {
+            other.instance == summary.instance && other.configuration == second_configuration
+        });
+
+        if let Some(other) = other {
+            first.push(summary.prd.mean);
+            second.push(other.prd.mean);
+        }
+    }
+
+    (first, second)
+}
+
+pub fn wilcoxon_signed_rank(first: &[f64], second: &[f64]) -> Option<TestResult> {
+    if first.len() != second.len() {
+        return None;
+    }
+
+    let differences = first
+        .iter()
+        .zip(second)
+        .map(|(first, second)| first - second)
+        .filter(|&difference| difference != 0.0)
+        .collect::<Vec<_>>();
+
+    if differences.is_empty() {
+        return None;
+    }
+
+    let n = differences.len() as f64;
+
+    let absolute = differences
+        .iter()
+        .map(|difference| difference.abs())
+        .collect::<Vec<_>>();
+
+    let (ranks, ties) = average_ranks(&absolute);
+
+    let positive_ranks = differences
+        .iter()
+        .zip(&ranks)
+        .filter(|(&difference, _)| difference > 0.0)
+        .map(|(_, rank)| rank)
+        .sum::<f64>();
+
+    let mean = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - ties / 48.0;
+
+    Some(normal_test(positive_ranks, mean, variance))
+}
+
+pub fn mann_whitney_u(first: &[f64], second: &[f64]) -> Option<TestResult> {
+    if first.is_empty() || second.is_empty() {
+        return None;
+    }
+
+    let n1 = first.len() as f64;
+    let n2 = second.len() as f64;
+    let n = n1 + n2;
+
+    let combined = first.iter().chain(second).copied().collect::<Vec<_>>();
+
+    let (ranks, ties) = average_ranks(&combined);
+
+    let first_ranks = ranks[..first.len()].iter().sum::<f64>();
+    let u = first_ranks - n1 * (n1 + 1.0) / 2.0;
+
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+
+    Some(normal_test(u, mean, variance))
+}
+
+fn average_ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order = (0..values.len()).collect::<Vec<_>>();
+    order.sort_by(|&first, &second| values[first].total_cmp(&values[second]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = 0.0;
+
+    let mut start = 0;
+
+    while start < order.len() {
+        let mut end = start + 1;
+
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+
+        let rank = (start + end + 1) as f64 / 2.0;
+
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+
+        let tied = (end - start) as f64;
+        ties += tied.powi(3) - tied;
+
+        start = end;
+    }
+
+    (ranks, ties)
+}
+
+fn normal_test(statistic: f64, mean: f64, variance: f64) -> TestResult {
+    if variance <= 0.0 {
+        return TestResult {
+            statistic,
+            z: 0.0,
+            p_value: 1.0,
+        };
+    }
+
+    let correction = if statistic > mean {
+        -0.5
+    } else if statistic < mean {
+        0.5
+    } else {
+        0.0
+    };
+
+    let z = (statistic - mean + correction) / variance.sqrt();
+
+    TestResult {
+        statistic,
+        z,
+        p_value: (2.0 * (1.0 - normal_cdf(z.abs()))).min(1.0),
+    }
+}
+
+fn t_quantile(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => 0.0,
+        df if df <= T_975.len() => T_975[df - 1],
+        df => {
+            let z: f64 = 1.959964;
+
+            z + (z.powi(3) + z) / (4.0 * df as f64)
+        }
+    }
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    let value = 1.0 - polynomial * (-x * x).exp();
+
+    if x < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_computed_correctly() {
+        let summary = Summary::new(&[4.0, 1.0, 3.0, 2.0]).unwrap();
+
+        assert_eq!(2.5, summary.mean);
+        assert_eq!(2.5, summary.median);
+        assert_eq!((1.0, 4.0), (summary.min, summary.max));
+        assert!((summary.stddev - (5.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert!((summary.ci_high - summary.mean - 2.0543).abs() < 1e-3);
+
+        assert_eq!(3.0, Summary::new(&[3.0, 1.0, 7.0]).unwrap().median);
+        assert_eq!(None, Summary::new(&[]));
+    }
+
+    #[test]
+    fn rank_tests_match_normal_approximation() {
+        let first = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let second = [6.0, 7.0, 8.0, 9.0, 10.0];
+
+        let result = mann_whitney_u(&first, &second).unwrap();
+
+        assert_eq!(0.0, result.statistic);
+        assert!((result.p_value - 0.01219).abs() < 1e-4);
+
+        let first = [11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 17.0, 18.0];
+        let second = [10.0; 8];
+
+        let result = wilcoxon_signed_rank(&first, &second).unwrap();
+
+        assert_eq!(36.0, result.statistic);
+        assert!((result.p_value - 0.01427).abs() < 1e-4);
+
+        let result = wilcoxon_signed_rank(&[1.0, 2.0, 3.0], &[2.0, 1.0, 3.0]).unwrap();
+
+        assert!((result.p_value - 1.0).abs() < 1e-6);
+    }
+}
@@ -1,7 +1,11 @@
 use genetyk::benchmarking;
+use genetyk::config::Experiment;
 
 fn main() {
-    // benchmarking::all_files_symmetric_vs_asymmetric();
-    // benchmarking::all_files_population_size_dependence();
-    benchmarking::all_files_memetic_fraction_dependence();
+    // let experiment = Experiment::from_file("experiments/symmetric_vs_asymmetric.toml");
+    // let experiment = Experiment::from_file("experiments/population_dependence.toml");
+    let experiment = Experiment::from_file("experiments/memetic_dependence.toml");
+
+    benchmarking::run_experiment(&experiment.expect("couldn't read experiment"))
+        .expect("experiment failed");
 }
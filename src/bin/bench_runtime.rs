@@ -2,5 +2,6 @@ use genetyk::benchmarking;
 
 fn main() {
     // benchmarking::bench_runtime(4);
-    benchmarking::bench_threads();
+    // benchmarking::bench_threads();
+    benchmarking::bench_worker_pool();
 }
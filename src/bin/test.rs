@@ -1,4 +1,4 @@
-use genetyk::genetic::Genetic;
+use genetyk::parameters::Parameters;
 use tsp_parser::*;
 
 fn main() {
@@ -24,42 +24,7 @@ fn run_from_file(file_name: &str, opt: u32) {
 
     let tsp = TspParser::from_file(file_name).unwrap();
 
-    let dimension = tsp.get_dimension();
-
-    let iterations = if file_name.contains("atsp") {
-        10 * dimension
-    } else {
-        dimension
-    };
-    
-    let population_size = 10 * dimension;
-
-    let elites = if population_size / 2 == 0 {
-        1
-    } else {
-        population_size / 3
-    };
-
-    let pairs = population_size / 2;
-    let kid_prob = 0.9;
-    let mutation_prob = 0.02;
-    let stagnation_iter = iterations / 10;
-    let mutation_steps = 4;
-    let memetic_fraction = 0.1;
-
-    let genetic = Genetic::new(
-        opt,
-        iterations,
-        population_size,
-        elites,
-        pairs,
-        kid_prob,
-        mutation_prob,
-        stagnation_iter,
-        mutation_steps,
-        4,
-        memetic_fraction,
-    );
+    let genetic = Parameters::for_problem(&tsp).to_genetic(opt);
 
     let route = genetic.get_route(&tsp);
     let route_len = tsp.get_route_len(&route).unwrap();
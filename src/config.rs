@@ -0,0 +1,312 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::{Crossover, GeneticError, Mutation, OperatorSelection, Replacement};
+use crate::parameters::Parameters;
+use crate::problem::PermutationProblem;
+
+const DEFAULT_REPETITIONS: usize = 10;
+
+macro_rules! parameters {
+    ($($field:ident: $type:ty),* $(,)?) => {
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct ParameterOverrides {
+            $(pub $field: Option<$type>,)*
+        }
+
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct ParameterGrid {
+            $(pub $field: Vec<$type>,)*
+        }
+
+        impl ParameterOverrides {
+            pub fn merged(&self, other: &ParameterOverrides) -> ParameterOverrides {
+                ParameterOverrides {
+                    $($field: other.$field.or(self.$field),)*
+                }
+            }
+        }
+
+        impl ParameterGrid {
+            pub fn configurations(
+                &self,
+                base: &ParameterOverrides,
+            ) -> Vec<(String, ParameterOverrides)> {
+                let configurations = vec![(String::new(), base.clone())];
+
+                $(
+                    let configurations = expand(
+                        configurations,
+                        stringify!($field),
+                        &self.$field,
+                        |overrides, value| overrides.$field = Some(value),
+                    );
+                )*
+
+                configurations
+            }
+        }
+    };
+}
+
+parameters! {
+    iterations: usize,
+    population_size: usize,
+    population_factor: usize,
+    elites_count: usize,
+    pair_count: usize,
+    crossing_prob: f64,
+    mutation_prob: f64,
+    stagnation_iter: usize,
+    max_mutation_multiply: usize,
+    threads: usize,
+    memetic_fraction: f64,
+    crossover: Crossover,
+    mutation: Mutation,
+    self_adaptive: bool,
+    operator_selection: OperatorSelection,
+    replacement: Replacement,
+}
+
+impl ParameterOverrides {
+    pub fn from_file(path: &str) -> Result<ParameterOverrides, GeneticError> {
+        read_config(path)
+    }
+
+    pub fn to_file(&self, path: &str) -> Result<(), GeneticError> {
+        write_config(path, self)
+    }
+
+    pub fn resolve<P: PermutationProblem>(&self, problem: &P) -> Parameters {
+        let mut parameters = Parameters::for_problem(problem);
+
+        if let Some(iterations) = self.iterations {
+            parameters = parameters.with_iterations(iterations);
+        }
+
+        if let Some(factor) = self.population_factor {
+            parameters = parameters.with_population_size(factor * problem.dimension());
+        }
+
+        if let Some(population_size) = self.population_size {
+            parameters = parameters.with_population_size(population_size);
+        }
+
+        Parameters {
+            elites_count: self.elites_count.unwrap_or(parameters.elites_count),
+            pair_count: self.pair_count.unwrap_or(parameters.pair_count),
+            crossing_prob: self.crossing_prob.unwrap_or(parameters.crossing_prob),
+            mutation_prob: self.mutation_prob.unwrap_or(parameters.mutation_prob),
+            stagnation_iter: self.stagnation_iter.unwrap_or(parameters.stagnation_iter),
+            max_mutation_multiply: self
+                .max_mutation_multiply
+                .unwrap_or(parameters.max_mutation_multiply),
+            threads: self.threads.unwrap_or(parameters.threads),
+            memetic_fraction: self.memetic_fraction.unwrap_or(parameters.memetic_fraction),
+            crossover: self.crossover.unwrap_or(parameters.crossover),
+            mutation: self.mutation.unwrap_or(parameters.mutation),
+            self_adaptive: self.self_adaptive.unwrap_or(parameters.self_adaptive),
+            operator_selection: self
+                .operator_selection
+                .unwrap_or(parameters.operator_selection),
+            replacement: self.replacement.unwrap_or(parameters.replacement),
+            ..parameters
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InstanceEntry {
+    pub path: String,
+    pub opt: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Experiment {
+    pub name: String,
+    pub instances: Vec<InstanceEntry>,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_parallel_instances")]
+    pub parallel_instances: usize,
+    #[serde(default)]
+    pub parameters: ParameterOverrides,
+    #[serde(default)]
+    pub grid: ParameterGrid,
+}
+
+impl Experiment {
+    pub fn from_file(path: &str) -> Result<Experiment, GeneticError> {
+        read_config(path)
+    }
+
+    pub fn from_toml(config: &str) -> Result<Experiment, GeneticError> {
+        toml::from_str(config).map_err(|err| GeneticError::InvalidFile(err.to_string()))
+    }
+
+    pub fn configurations(&self) -> Vec<(String, ParameterOverrides)> {
+        self.grid.configurations(&self.parameters)
+    }
+}
+
+fn default_repetitions() -> usize {
+    DEFAULT_REPETITIONS
+}
+
+fn default_parallel_instances() -> usize {
+    1
+}
+
+fn expand<T: Copy + std::fmt::Display>(
+    configurations: Vec<(String, ParameterOverrides)>,
+    name: &str,
+    values: &[T],
+    set: impl Fn(&mut ParameterOverrides, T),
+) -> Vec<(String, ParameterOverrides)> {
+    if values.is_empty() {
+        return configurations;
+    }
+
+    let mut expanded = Vec::with_capacity(configurations.len() * values.len());
+
+    for (label, overrides) in &configurations {
+        for &value in values {
+            let mut overrides = overrides.clone();
+            set(&mut overrides, value);
+
+            let label = if label.is_empty() {
+                format!("{}={}", name, value)
+            } else {
+                format!("{},{}={}", label, name, value)
+            };
+
+            expanded.push((label, overrides));
+        }
+    }
+
+    expanded
+}
+
+pub fn read_config<T: DeserializeOwned>(path: &str) -> Result<T, GeneticError> {
+    let config = std::fs::read_to_string(path)
+        .map_err(|err| GeneticError::InvalidFile(format!("{}: {}", path, err)))?;
+
+    if is_json(path) {
+        serde_json::from_str(&config).map_err(|err| GeneticError::InvalidFile(err.to_string()))
+    } else {
+        toml::from_str(&config).map_err(|err| GeneticError::InvalidFile(err.to_string()))
+    }
+}
+
+pub fn write_config<T: Serialize>(path: &str, config: &T) -> Result<(), GeneticError> {
+    let config = if is_json(path) {
+        serde_json::to_string_pretty(config).map_err(|err| err.to_string())
+    } else {
+        toml::to_string(config).map_err(|err| err.to_string())
+    };
+
+    config
+        .and_then(|config| std::fs::write(path, config).map_err(|err| err.to_string()))
+        .map_err(|err| GeneticError::InvalidFile(format!("{}: {}", path, err)))
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_utils::line_instance;
+
+    const EXPERIMENT: &str = r#"
+name = "population_dependence"
+repetitions = 3
+
+instances = [
+    { path = "berlin52.tsp", opt = 7542 },
+    { path = "br17.atsp", opt = 39 },
+]
+
+[parameters]
+memetic_fraction = 0.2
+crossover = "order"
+
+[grid]
+population_factor = [1, 5]
+mutation_prob = [0.01, 0.02, 0.05]
+"#;
+
+    #[test]
+    fn experiment_grid_expanded() {
+        let experiment = Experiment::from_toml(EXPERIMENT).unwrap();
+
+        assert_eq!(2, experiment.instances.len());
+        assert_eq!(3, experiment.repetitions);
+
+        let configurations = experiment.configurations();
+
+        assert_eq!(6, configurations.len());
+        assert_eq!(
+            "population_factor=5,mutation_prob=0.01",
+            configurations[3].0
+        );
+
+        let parameters = configurations[3].1.resolve(&line_instance(20));
+
+        assert_eq!(100, parameters.population_size);
+        assert_eq!(50, parameters.elites_count);
+        assert_eq!(0.01, parameters.mutation_prob);
+        assert_eq!(0.2, parameters.memetic_fraction);
+        assert_eq!(Crossover::Order, parameters.crossover);
+
+        assert!(Experiment::from_toml("name = \"empty\"\npopulation = 10").is_err());
+    }
+
+    #[test]
+    fn overrides_merged_in_order() {
+        let file: ParameterOverrides =
+            serde_json::from_str(r#"{ "iterations": 50, "mutation": "insert" }"#).unwrap();
+
+        let flags = ParameterOverrides {
+            iterations: Some(70),
+            ..ParameterOverrides::default()
+        };
+
+        let parameters = file.merged(&flags).resolve(&line_instance(20));
+
+        assert_eq!(70, parameters.iterations);
+        assert_eq!(14, parameters.stagnation_iter);
+        assert_eq!(Mutation::Insert, parameters.mutation);
+
+        for extension in ["toml", "json"] {
+            let path = std::env::temp_dir()
+                .join(format!(
+                    "genetyk-overrides-{}.{}",
+                    std::process::id(),
+                    extension
+                ))
+                .to_string_lossy()
+                .to_string();
+
+            file.to_file(&path).unwrap();
+
+            let written = ParameterOverrides::from_file(&path);
+
+            std::fs::remove_file(&path).ok();
+
+            assert_eq!(Ok(file.clone()), written);
+        }
+    }
+}
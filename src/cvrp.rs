@@ -0,0 +1,324 @@
+use crate::genetic::{Genetic, GeneticError};
+use crate::instance::Instance;
+use crate::problem::PermutationProblem;
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone)]
+pub struct CvrpInstance {
+    instance: Instance,
+    depot: usize,
+    capacity: u32,
+    demands: Vec<u32>,
+    customers: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CvrpSolution {
+    pub routes: Vec<Vec<usize>>,
+    pub cost: u32,
+}
+
+impl CvrpInstance {
+    pub fn new(
+        instance: Instance,
+        depot: usize,
+        capacity: u32,
+        demands: Vec<u32>,
+    ) -> Result<CvrpInstance, GeneticError> {
+        let dimension = instance.dimension();
+
+        if depot >= dimension {
+            return Err(GeneticError::InvalidInstance(
+                "depot is not a node of the instance",
+            ));
+        }
+
+        if demands.len() != dimension {
+            return Err(GeneticError::InvalidInstance(
+                "every node of the instance needs a demand",
+            ));
+        }
+
+        if demands.iter().any(|&demand| demand > capacity) {
+            return Err(GeneticError::InvalidInstance(
+                "demand of a customer exceeds vehicle capacity",
+            ));
+        }
+
+        let customers = (0..dimension).filter(|&node| node != depot).collect();
+
+        Ok(CvrpInstance {
+            instance,
+            depot,
+            capacity,
+            demands,
+            customers,
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<CvrpInstance, GeneticError> {
+        let file = TsplibFile::from_file(path)?;
+
+        CvrpInstance::from_tsplib(&file)
+    }
+
+    pub fn from_tsplib(file: &TsplibFile) -> Result<CvrpInstance, GeneticError> {
+        let instance = file.to_instance()?;
+
+        let capacity = file.get_number::<u32>("CAPACITY")?;
+        let demands = file.get_node_values("DEMAND_SECTION", 0)?;
+
+        let depot = file.get_depot()?;
+
+        CvrpInstance::new(instance, depot, capacity, demands)
+    }
+
+    pub fn get_depot(&self) -> usize {
+        self.depot
+    }
+
+    pub fn get_capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn get_demand(&self, node: usize) -> u32 {
+        self.demands[node]
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<CvrpSolution, GeneticError> {
+        let giant_tour = genetic.try_solve(self)?;
+
+        self.split(&giant_tour).ok_or(GeneticError::InvalidRoute)
+    }
+
+    pub fn split(&self, giant_tour: &[usize]) -> Option<CvrpSolution> {
+        let (costs, predecessors) = self.split_labels(giant_tour)?;
+
+        let mut routes = Vec::new();
+        let mut end = giant_tour.len();
+
+        while end > 0 {
+            let start = predecessors[end];
+
+            let route = giant_tour[start..end]
+                .iter()
+                .map(|&customer| self.customers[customer])
+                .collect();
+
+            routes.push(route);
+            end = start;
+        }
+
+        routes.reverse();
+
+        Some(CvrpSolution {
+            routes,
+            cost: costs[giant_tour.len()],
+        })
+    }
+
+    fn split_labels(&self, giant_tour: &[usize]) -> Option<(Vec<u32>, Vec<usize>)> {
+        let customers_count = giant_tour.len();
+
+        let mut costs = vec![u32::MAX; customers_count + 1];
+        let mut predecessors = vec![0; customers_count + 1];
+
+        costs[0] = 0;
+
+        for start in 0..customers_count {
+            if costs[start] == u32::MAX {
+                continue;
+            }
+
+            let mut load: u32 = 0;
+            let mut route_cost: u32 = 0;
+
+            for end in start..customers_count {
+                let node = self.customers[giant_tour[end]];
+
+                load = match load.checked_add(self.demands[node]) {
+                    Some(load) if load <= self.capacity => load,
+                    _ => break,
+                };
+
+                let next_cost = if end == start {
+                    Some(self.instance.get_distance(self.depot, node))
+                } else {
+                    let previous_node = self.customers[giant_tour[end - 1]];
+
+                    route_cost.checked_add(self.instance.get_distance(previous_node, node))
+                };
+
+                route_cost = match next_cost {
+                    Some(route_cost) => route_cost,
+                    None => break,
+                };
+
+                let cost = match costs[start]
+                    .checked_add(route_cost)
+                    .and_then(|cost| cost.checked_add(self.instance.get_distance(node, self.depot)))
+                {
+                    Some(cost) => cost,
+                    None => continue,
+                };
+
+                if cost < costs[end + 1] {
+                    costs[end + 1] = cost;
+                    predecessors[end + 1] = start;
+                }
+            }
+        }
+
+        if costs[customers_count] == u32::MAX {
+            None
+        } else {
+            Some((costs, predecessors))
+        }
+    }
+}
+
+impl PermutationProblem for CvrpInstance {
+    fn dimension(&self) -> usize {
+        self.customers.len()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.instance.is_symmetric()
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        if route.len() != self.customers.len() {
+            return None;
+        }
+
+        let mut visited = vec![false; route.len()];
+
+        for &customer in route {
+            if customer >= route.len() || visited[customer] {
+                return None;
+            }
+
+            visited[customer] = true;
+        }
+
+        let (costs, _) = self.split_labels(route)?;
+
+        Some(costs[route.len()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::instance::Metric;
+
+    const CVRP_FILE: &str = "NAME : line
+TYPE : CVRP
+DIMENSION : 5
+EDGE_WEIGHT_TYPE : EUC_2D
+CAPACITY : 10
+NODE_COORD_SECTION
+1 0 0
+2 10 0
+3 20 0
+4 -10 0
+5 -20 0
+DEMAND_SECTION
+1 0
+2 5
+3 5
+4 5
+5 5
+DEPOT_SECTION
+1
+-1
+EOF";
+
+    #[test]
+    fn file_parsed_correctly() {
+        let cvrp = CvrpInstance::from_tsplib(&TsplibFile::parse(CVRP_FILE).unwrap()).unwrap();
+
+        assert_eq!(0, cvrp.get_depot());
+        assert_eq!(10, cvrp.get_capacity());
+        assert_eq!(4, cvrp.dimension());
+        assert_eq!(5, cvrp.get_demand(3));
+    }
+
+    #[test]
+    fn giant_tour_split_optimally() {
+        let cvrp = CvrpInstance::from_tsplib(&TsplibFile::parse(CVRP_FILE).unwrap()).unwrap();
+
+        let solution = cvrp.split(&[0, 1, 2, 3]).unwrap();
+
+        assert_eq!(vec![vec![1, 2], vec![3, 4]], solution.routes);
+        assert_eq!(80, solution.cost);
+
+        let solution = cvrp.split(&[0, 2, 1, 3]).unwrap();
+
+        assert_eq!(120, solution.cost);
+        assert_eq!(Some(solution.cost), cvrp.evaluate(&[0, 2, 1, 3]));
+    }
+
+    #[test]
+    fn overflowing_loads_and_costs_split_as_infeasible() {
+        let instance =
+            Instance::from_matrix(vec![vec![0, 1, 1], vec![1, 0, 1], vec![1, 1, 0]]).unwrap();
+
+        let cvrp = CvrpInstance::new(instance, 0, u32::MAX, vec![0, u32::MAX, 1]).unwrap();
+
+        let solution = cvrp.split(&[0, 1]).unwrap();
+
+        assert_eq!(vec![vec![1], vec![2]], solution.routes);
+        assert_eq!(4, solution.cost);
+
+        let far = u32::MAX / 2 + 1;
+        let instance =
+            Instance::from_matrix(vec![vec![0, far, 1], vec![far, 0, far], vec![1, far, 0]])
+                .unwrap();
+
+        let cvrp = CvrpInstance::new(instance, 0, 10, vec![0, 1, 1]).unwrap();
+
+        assert_eq!(None, cvrp.split(&[0, 1]));
+        assert_eq!(None, cvrp.evaluate(&[1, 0]));
+    }
+
+    #[test]
+    fn cvrp_solved_with_capacity_respected() {
+        let coordinates = vec![
+            (50.0, 50.0),
+            (10.0, 80.0),
+            (20.0, 15.0),
+            (90.0, 40.0),
+            (65.0, 95.0),
+            (35.0, 60.0),
+            (80.0, 5.0),
+            (5.0, 40.0),
+        ];
+
+        let instance = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+        let demands = vec![0, 4, 6, 3, 5, 7, 2, 4];
+
+        let cvrp = CvrpInstance::new(instance, 0, 12, demands).unwrap();
+
+        let genetic = Genetic::new(0, 30, 10, 3, 5, 0.9, 0.02, 10, 4, 2, 0.5);
+
+        let solution = cvrp.solve(&genetic).unwrap();
+
+        let mut nodes = solution
+            .routes
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        nodes.sort();
+
+        assert_eq!((1..8).collect::<Vec<_>>(), nodes);
+
+        for route in &solution.routes {
+            let load = route.iter().map(|&node| cvrp.get_demand(node)).sum::<u32>();
+
+            assert!(load <= cvrp.get_capacity());
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::{GeneticError, GeneticRng};
+use crate::instance::{Instance, Metric};
+
+const DEFAULT_EXTENT: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    Uniform,
+    Clustered,
+    Grid,
+    Symmetric,
+    Asymmetric,
+}
+
+impl FromStr for Layout {
+    type Err = GeneticError;
+
+    fn from_str(layout: &str) -> Result<Layout, GeneticError> {
+        match layout.to_ascii_lowercase().as_str() {
+            "uniform" => Ok(Layout::Uniform),
+            "clustered" => Ok(Layout::Clustered),
+            "grid" => Ok(Layout::Grid),
+            "symmetric" => Ok(Layout::Symmetric),
+            "asymmetric" => Ok(Layout::Asymmetric),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "layout has to be one of uniform, clustered, grid, symmetric or asymmetric",
+            )),
+        }
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layout::Uniform => write!(f, "uniform"),
+            Layout::Clustered => write!(f, "clustered"),
+            Layout::Grid => write!(f, "grid"),
+            Layout::Symmetric => write!(f, "symmetric"),
+            Layout::Asymmetric => write!(f, "asymmetric"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Generated {
+    Coordinates(Vec<(f64, f64)>),
+    Matrix(Vec<Vec<u32>>),
+}
+
+impl Generated {
+    pub fn to_instance(&self) -> Result<Instance, GeneticError> {
+        match self {
+            Generated::Coordinates(coordinates) => Ok(Instance::from_coordinates(
+                coordinates,
+                Metric::RoundedEuclidean,
+            )),
+            Generated::Matrix(matrix) => Instance::from_matrix(matrix.clone()),
+        }
+    }
+
+    pub fn to_tsplib(&self, name: &str) -> String {
+        let mut text = String::new();
+
+        match self {
+            Generated::Coordinates(coordinates) => {
+                writeln!(text, "NAME : {}", name).unwrap();
+                writeln!(text, "TYPE : TSP").unwrap();
+                writeln!(text, "DIMENSION : {}", coordinates.len()).unwrap();
+                writeln!(text, "EDGE_WEIGHT_TYPE : EUC_2D").unwrap();
+                writeln!(text, "NODE_COORD_SECTION").unwrap();
+
+                for (i, (x, y)) in coordinates.iter().enumerate() {
+                    writeln!(text, "{} {} {}", i + 1, x, y).unwrap();
+                }
+            }
+            Generated::Matrix(matrix) => {
+                let symmetric = (0..matrix.len())
+                    .all(|i| (i + 1..matrix.len()).all(|j| matrix[i][j] == matrix[j][i]));
+
+                writeln!(text, "NAME : {}", name).unwrap();
+                writeln!(text, "TYPE : {}", if symmetric { "TSP" } else { "ATSP" }).unwrap();
+                writeln!(text, "DIMENSION : {}", matrix.len()).unwrap();
+                writeln!(text, "EDGE_WEIGHT_TYPE : EXPLICIT").unwrap();
+                writeln!(text, "EDGE_WEIGHT_FORMAT : FULL_MATRIX").unwrap();
+                writeln!(text, "EDGE_WEIGHT_SECTION").unwrap();
+
+                for row in matrix {
+                    let row = row.iter().map(u32::to_string).collect::<Vec<_>>();
+
+                    writeln!(text, "{}", row.join(" ")).unwrap();
+                }
+            }
+        }
+
+        text.push_str("EOF\n");
+
+        text
+    }
+
+    pub fn write_tsplib(&self, path: &str, name: &str) -> Result<(), GeneticError> {
+        std::fs::write(path, self.to_tsplib(name))
+            .map_err(|err| GeneticError::InvalidFile(format!("{}: {}", path, err)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    layout: Layout,
+    dimension: usize,
+    seed: u64,
+    extent: u32,
+    clusters: usize,
+}
+
+impl Generator {
+    pub fn new(layout: Layout, dimension: usize, seed: u64) -> Self {
+        Generator {
+            layout,
+            dimension,
+            seed,
+            extent: DEFAULT_EXTENT,
+            clusters: (dimension / 10).max(1),
+        }
+    }
+
+    pub fn with_extent(mut self, extent: u32) -> Self {
+        self.extent = extent.max(1);
+        self
+    }
+
+    pub fn with_clusters(mut self, clusters: usize) -> Self {
+        self.clusters = clusters.max(1);
+        self
+    }
+
+    pub fn get_name(&self) -> String {
+        format!("{}-s{}-{}", self.layout, self.seed, self.dimension)
+    }
+
+    pub fn generate(&self) -> Generated {
+        let mut rng = GeneticRng::seed_from_u64(self.seed);
+
+        match self.layout {
+            Layout::Uniform => Generated::Coordinates(self.uniform(&mut rng)),
+            Layout::Clustered => Generated::Coordinates(self.clustered(&mut rng)),
+            Layout::Grid => Generated::Coordinates(self.grid()),
+            Layout::Symmetric => Generated::Matrix(self.matrix(true, &mut rng)),
+            Layout::Asymmetric => Generated::Matrix(self.matrix(false, &mut rng)),
+        }
+    }
+
+    pub fn instance(&self) -> Result<Instance, GeneticError> {
+        self.generate().to_instance()
+    }
+
+    fn uniform(&self, rng: &mut GeneticRng) -> Vec<(f64, f64)> {
+        (0..self.dimension)
+            .map(|_| {
+                (
+                    rng.gen_range(0..=self.extent) as f64,
+                    rng.gen_range(0..=self.extent) as f64,
+                )
+            })
+            .collect()
+    }
+
+    fn clustered(&self, rng: &mut GeneticRng) -> Vec<(f64, f64)> {
+        let extent = self.extent as f64;
+        let spread = extent / (2.0 * (self.clusters as f64).sqrt() + 2.0);
+
+        let centers = (0..self.clusters)
+            .map(|_| (rng.gen_range(0.0..=extent), rng.gen_range(0.0..=extent)))
+            .collect::<Vec<_>>();
+
+        (0..self.dimension)
+            .map(|_| {
+                let (center_x, center_y) = centers[rng.gen_range(0..centers.len())];
+                let (offset_x, offset_y) = gaussian_pair(rng);
+
+                (
+                    (center_x + spread * offset_x).clamp(0.0, extent).round(),
+                    (center_y + spread * offset_y).clamp(0.0, extent).round(),
+                )
+            })
+            .collect()
+    }
+
+    fn grid(&self) -> Vec<(f64, f64)> {
+        let side = (self.dimension as f64).sqrt().ceil().max(1.0) as usize;
+        let spacing = (self.extent as usize / side).max(1);
+
+        (0..self.dimension)
+            .map(|i| ((i % side * spacing) as f64, (i / side * spacing) as f64))
+            .collect()
+    }
+
+    fn matrix(&self, symmetric: bool, rng: &mut GeneticRng) -> Vec<Vec<u32>> {
+        let weights = (0..self.dimension)
+            .map(|_| {
+                (0..self.dimension)
+                    .map(|_| rng.gen_range(1..=self.extent))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        (0..self.dimension)
+            .map(|i| {
+                (0..self.dimension)
+                    .map(|j| match i.cmp(&j) {
+                        Ordering::Equal => 0,
+                        Ordering::Greater if symmetric => weights[j][i],
+                        _ => weights[i][j],
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn gaussian_pair(rng: &mut GeneticRng) -> (f64, f64) {
+    let radius = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt();
+    let angle = 2.0 * std::f64::consts::PI * rng.gen::<f64>();
+
+    (radius * angle.cos(), radius * angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::problem::PermutationProblem;
+    use crate::tsplib::TsplibFile;
+
+    #[test]
+    fn generated_instances_reproducible() {
+        for layout in [
+            Layout::Uniform,
+            Layout::Clustered,
+            Layout::Grid,
+            Layout::Symmetric,
+            Layout::Asymmetric,
+        ] {
+            let generator = Generator::new(layout, 30, 7);
+
+            assert_eq!(generator.generate(), generator.generate());
+
+            let instance = generator.instance().unwrap();
+
+            assert_eq!(30, instance.dimension());
+            assert_eq!(layout != Layout::Asymmetric, instance.is_symmetric());
+        }
+
+        assert_ne!(
+            Generator::new(Layout::Uniform, 30, 7).generate(),
+            Generator::new(Layout::Uniform, 30, 8).generate()
+        );
+    }
+
+    #[test]
+    fn tsplib_written_correctly() {
+        for layout in [Layout::Clustered, Layout::Asymmetric] {
+            let generated = Generator::new(layout, 12, 3).generate();
+
+            let instance = generated.to_instance().unwrap();
+            let parsed = TsplibFile::parse(&generated.to_tsplib("generated"))
+                .unwrap()
+                .to_instance()
+                .unwrap();
+
+            assert_eq!(instance.dimension(), parsed.dimension());
+
+            for i in 0..12 {
+                for j in 0..12 {
+                    assert_eq!(instance.get_distance(i, j), parsed.get_distance(i, j));
+                }
+            }
+        }
+    }
+}
@@ -1,22 +1,73 @@
+mod bandit;
+mod checkpoint;
+mod error;
 mod genetic_state;
+mod nsga;
+mod operators;
 mod population_member;
+mod replacement;
+mod worker_pool;
 
-use std::collections::HashSet;
 use std::io::Write;
-use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use rand::prelude::*;
+use serde::Serialize;
 use tsp_parser::{Tsp, TspHeuristic};
 
+use bandit::OperatorBandit;
 use genetic_state::GeneticState;
 use population_member::PopulationMember;
+use worker_pool::{Job, WorkerPool};
 
-fn prd(fx: u32, fref: u32) -> f64 {
+use crate::problem::PermutationProblem;
+
+pub use bandit::{ArmStats, OperatorSelection, OperatorStats};
+pub use checkpoint::CHECKPOINT_VERSION;
+pub use error::GeneticError;
+pub use nsga::{crowding_distances, non_dominated_sort, ParetoMember};
+pub use operators::{Crossover, Mutation, Strategy, Variation};
+pub use replacement::Replacement;
+
+pub type GeneticRng = rand_pcg::Pcg64Mcg;
+
+const MAX_DUPLICATE_ATTEMPTS: usize = 1000;
+
+pub fn prd(fx: u32, fref: u32) -> f64 {
     let fx = fx as f64;
     let fref = fref as f64;
 
     100.0 * (fx - fref) / fref
 }
 
+fn is_permutation(route: &[usize], dimension: usize) -> bool {
+    let mut visited = vec![false; dimension];
+
+    route.len() == dimension
+        && route
+            .iter()
+            .all(|&city| city < dimension && !std::mem::replace(&mut visited[city], true))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdaptationStats {
+    pub generation: usize,
+    pub mean_mutation_prob: f64,
+    pub min_mutation_prob: f64,
+    pub max_mutation_prob: f64,
+    pub crossover_counts: Vec<(Crossover, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunStats {
+    pub generations: usize,
+    pub elapsed_secs: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub adaptation: Vec<AdaptationStats>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub operators: Vec<OperatorStats>,
+}
+
 pub struct Genetic {
     opt: u32,
     iterations: usize,
@@ -29,6 +80,15 @@ pub struct Genetic {
     max_mutation_multiply: usize,
     threads: usize,
     memetic_fraction: f64,
+    crossover: Crossover,
+    mutation: Mutation,
+    self_adaptive: bool,
+    operator_selection: OperatorSelection,
+    replacement: Replacement,
+    seed: Option<u64>,
+    checkpoint_path: Option<String>,
+    checkpoint_interval: usize,
+    initial_routes: Vec<Vec<usize>>,
 }
 
 impl Genetic {
@@ -45,6 +105,12 @@ impl Genetic {
         threads: usize,
         memetic_fraction: f64,
     ) -> Self {
+        let threads = if threads == 0 {
+            Genetic::default_threads()
+        } else {
+            threads
+        };
+
         Genetic {
             opt,
             iterations,
@@ -57,6 +123,171 @@ impl Genetic {
             max_mutation_multiply,
             threads,
             memetic_fraction,
+            crossover: Crossover::Pmx,
+            mutation: Mutation::Auto,
+            self_adaptive: false,
+            operator_selection: OperatorSelection::Fixed,
+            replacement: Replacement::Generational,
+            seed: None,
+            checkpoint_path: None,
+            checkpoint_interval: 0,
+            initial_routes: vec![],
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        opt: u32,
+        iterations: usize,
+        population_size: usize,
+        elites_count: usize,
+        pair_count: usize,
+        crossing_prob: f64,
+        mutation_prob: f64,
+        stagnation_iter: usize,
+        max_mutation_multiply: usize,
+        threads: usize,
+        memetic_fraction: f64,
+    ) -> Result<Self, GeneticError> {
+        Genetic::new(
+            opt,
+            iterations,
+            population_size,
+            elites_count,
+            pair_count,
+            crossing_prob,
+            mutation_prob,
+            stagnation_iter,
+            max_mutation_multiply,
+            threads,
+            memetic_fraction,
+        )
+        .validated()
+    }
+
+    pub fn with_operators(mut self, crossover: Crossover, mutation: Mutation) -> Self {
+        self.crossover = crossover;
+        self.mutation = mutation;
+        self
+    }
+
+    pub fn with_self_adaptation(mut self, self_adaptive: bool) -> Self {
+        self.self_adaptive = self_adaptive;
+        self
+    }
+
+    pub fn with_operator_selection(mut self, operator_selection: OperatorSelection) -> Self {
+        self.operator_selection = operator_selection;
+        self
+    }
+
+    pub fn with_replacement(mut self, replacement: Replacement) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_checkpoint(mut self, path: &str, interval: usize) -> Self {
+        self.checkpoint_path = Some(path.to_string());
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    pub fn with_initial_routes(mut self, routes: Vec<Vec<usize>>) -> Self {
+        self.initial_routes = routes;
+        self
+    }
+
+    pub fn validated(self) -> Result<Self, GeneticError> {
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    fn validate(&self) -> Result<(), GeneticError> {
+        if self.population_size < 2 {
+            return Err(GeneticError::InvalidConfiguration(
+                "population size has to be at least 2",
+            ));
+        }
+
+        if self.elites_count == 0 || self.elites_count > self.population_size {
+            return Err(GeneticError::InvalidConfiguration(
+                "elites count has to be between 1 and population size",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.crossing_prob) {
+            return Err(GeneticError::InvalidConfiguration(
+                "crossing probability has to be between 0 and 1",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.mutation_prob) {
+            return Err(GeneticError::InvalidConfiguration(
+                "mutation probability has to be between 0 and 1",
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.memetic_fraction) {
+            return Err(GeneticError::InvalidConfiguration(
+                "memetic fraction has to be between 0 and 1",
+            ));
+        }
+
+        if self.max_mutation_multiply == 0 {
+            return Err(GeneticError::InvalidConfiguration(
+                "max mutation multiply has to be at least 1",
+            ));
+        }
+
+        if self.self_adaptive && self.operator_selection != OperatorSelection::Fixed {
+            return Err(GeneticError::InvalidConfiguration(
+                "self-adaptation and adaptive operator selection cannot be combined",
+            ));
+        }
+
+        if self.checkpoint_path.is_some() && self.checkpoint_interval == 0 {
+            return Err(GeneticError::InvalidConfiguration(
+                "checkpoint interval has to be at least 1",
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn default_threads() -> usize {
+        std::thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1)
+    }
+
+    fn variation(&self, mutation_prob: f64) -> Variation {
+        Variation::new(self.crossover, self.mutation, mutation_prob)
+            .with_self_adaptation(self.self_adaptive)
+    }
+
+    fn new_rng(&self) -> GeneticRng {
+        match self.seed {
+            Some(seed) => GeneticRng::seed_from_u64(seed),
+            None => GeneticRng::from_entropy(),
+        }
+    }
+
+    fn print_progress(&self, generation: usize, best_route_len: u32) {
+        if self.opt > 0 {
+            eprintln!(
+                "{}\t {}\t {:.2}%",
+                generation,
+                best_route_len,
+                prd(best_route_len, self.opt)
+            );
+        } else {
+            eprintln!("{}\t {}", generation, best_route_len);
         }
     }
 
@@ -82,159 +313,268 @@ impl Genetic {
         }
     }
 
-    fn initalize_genetic_state(&self, tsp: &Tsp) -> GeneticState {
-        let population = Arc::new(Mutex::new(HashSet::with_capacity(2 * self.population_size)));
-        let elites = Arc::new(Mutex::new(Vec::with_capacity(self.elites_count)));
+    fn initalize_genetic_state<'scope, P: PermutationProblem>(
+        &self,
+        problem: &'scope P,
+        pool: &WorkerPool<'scope>,
+    ) -> Result<GeneticState, GeneticError> {
+        let mut genetic_state = GeneticState::new(
+            self.elites_count,
+            self.stagnation_iter,
+            self.mutation_prob,
+            self.new_rng(),
+        );
 
-        let elites_count = self.elites_count;
+        for route in &self.initial_routes {
+            if !is_permutation(route, problem.dimension()) {
+                return Err(GeneticError::InvalidRoute);
+            }
 
-        let population_left = Arc::new(Mutex::new(
-            (self.population_size as f64 * self.memetic_fraction) as usize,
-        ));
+            let member = PopulationMember::try_new(route.clone(), problem)?;
 
-        let mut threads = Vec::with_capacity(self.threads);
+            genetic_state.insert_member(member, self.elites_count);
+        }
 
-        for _ in 0..self.threads {
-            let population_clone = Arc::clone(&population);
-            let elites_clone = Arc::clone(&elites);
-            let population_left_clone = Arc::clone(&population_left);
+        let enhanced_count = (self.population_size as f64 * self.memetic_fraction) as usize;
 
-            let tsp = tsp.clone();
+        let work = worker_pool::split_work(enhanced_count, pool.get_threads());
 
-            threads.push(std::thread::spawn(move || loop {
-                let member = PopulationMember::gen_random_enhanced(&tsp);
+        let mut jobs: Vec<Job<'scope>> = Vec::with_capacity(work.len());
 
-                let mut population_left_locked = population_left_clone.lock().unwrap();
+        for range in work {
+            let members_count = range.len();
+            let seed = genetic_state.rng.gen::<u64>();
 
-                if *population_left_locked == 0 {
-                    break;
-                }
-
-                if population_clone.lock().unwrap().insert(member.clone()) {
-                    let mut elites = elites_clone.lock().unwrap();
+            jobs.push(Box::new(move || {
+                let mut rng = GeneticRng::seed_from_u64(seed);
+                let mut members = Vec::with_capacity(members_count);
 
-                    if elites.len() < elites_count {
-                        Genetic::insert_elite_begin(&mut elites, member);
-                    } else {
-                        Genetic::insert_elite(&mut elites, member);
-                    }
+                for _ in 0..members_count {
+                    members.push(PopulationMember::gen_random_enhanced(problem, &mut rng)?);
                 }
 
-                *population_left_locked -= 1;
+                Ok(members)
             }));
         }
 
-        for thread in threads {
-            thread.join().unwrap();
+        for members in pool.run(jobs)? {
+            for member in members {
+                genetic_state.insert_member(member, self.elites_count);
+            }
         }
 
-        {
-            let mut population_locked = population.lock().unwrap();
+        genetic_state.fill_random(self.population_size, self.elites_count, problem)?;
 
-            for _ in 0..self.population_size - population_locked.len() {
-                let mut member = PopulationMember::gen_random(tsp);
+        Ok(genetic_state)
+    }
 
-                while !population_locked.insert(member.clone()) {
-                    member = PopulationMember::gen_random(tsp);
-                }
+    pub fn try_solve<P: PermutationProblem>(
+        &self,
+        problem: &P,
+    ) -> Result<Vec<usize>, GeneticError> {
+        self.try_solve_with_stats(problem).map(|(route, _)| route)
+    }
 
-                let elites_len = elites.lock().unwrap().len();
+    pub fn try_solve_with_stats<P: PermutationProblem>(
+        &self,
+        problem: &P,
+    ) -> Result<(Vec<usize>, RunStats), GeneticError> {
+        self.validate()?;
 
-                if elites.lock().unwrap()[elites_len - 1].get_route_len() > member.get_route_len() {
-                    Genetic::insert_elite(&mut elites.lock().unwrap(), member);
-                }
-            }
+        let started = Instant::now();
+
+        if problem.dimension() < 2 {
+            return Err(GeneticError::InstanceTooSmall(problem.dimension()));
         }
 
-        GeneticState::new(population, elites)
+        std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, self.threads);
+
+            eprint!("Generatic initial population... ");
+            std::io::stderr().flush().ok();
+
+            let genetic_state = self.initalize_genetic_state(problem, &pool)?;
+
+            eprintln!("Done");
+
+            self.run_generations(problem, &pool, genetic_state, started)
+        })
     }
-}
 
-impl TspHeuristic for Genetic {
-    fn get_route(&self, tsp: &Tsp) -> Vec<usize> {
-        let dimension = tsp.get_dimension();
+    pub fn try_resume<P: PermutationProblem>(
+        &self,
+        problem: &P,
+        path: &str,
+    ) -> Result<Vec<usize>, GeneticError> {
+        self.try_resume_with_stats(problem, path)
+            .map(|(route, _)| route)
+    }
 
-        print!("Generatic initial population... ");
-        std::io::stdout().flush().unwrap();
+    pub fn try_resume_with_stats<P: PermutationProblem>(
+        &self,
+        problem: &P,
+        path: &str,
+    ) -> Result<(Vec<usize>, RunStats), GeneticError> {
+        self.validate()?;
 
-        let mut genetic_state = self.initalize_genetic_state(tsp);
+        let started = Instant::now();
 
-        println!("Done");
+        let genetic_state = checkpoint::load(path)?;
 
-        let mut best_route_len = genetic_state.elites.lock().unwrap()[0].get_route_len();
+        let matches_problem = !genetic_state.elites.is_empty()
+            && genetic_state
+                .population
+                .iter()
+                .chain(&genetic_state.elites)
+                .all(|member| problem.evaluate(member.get_route()) == Some(member.get_route_len()));
 
-        let mut stagnation_iter = self.stagnation_iter;
-        let mut mutation_multiplier = 1;
+        if !matches_problem {
+            return Err(GeneticError::InvalidCheckpoint(
+                "checkpoint does not match the instance".to_string(),
+            ));
+        }
 
-        let mut curr_mutation_prob = self.mutation_prob;
+        std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, self.threads);
 
-        println!(
-            "{}\t {}\t {:.2}%",
-            0,
-            best_route_len,
-            prd(best_route_len, self.opt)
-        );
+            self.run_generations(problem, &pool, genetic_state, started)
+        })
+    }
 
-        for i in 0..self.iterations {
-            let parents = genetic_state.parents_selection(self.pair_count);
-
-            genetic_state.cross_parents(
-                &parents,
-                self.crossing_prob,
-                dimension,
-                tsp,
-                curr_mutation_prob,
-                self.threads,
-                self.memetic_fraction,
-            );
+    fn run_generations<'scope, P: PermutationProblem>(
+        &self,
+        problem: &'scope P,
+        pool: &WorkerPool<'scope>,
+        mut genetic_state: GeneticState,
+        started: Instant,
+    ) -> Result<(Vec<usize>, RunStats), GeneticError> {
+        let dimension = problem.dimension();
+
+        let mut best_route_len = genetic_state.elites[0].get_route_len();
+        let mut adaptation = vec![];
+        let mut operators = vec![];
+
+        if self.self_adaptive {
+            genetic_state.assign_strategies(self.mutation_prob);
+        }
+
+        if self.operator_selection != OperatorSelection::Fixed && genetic_state.operators.is_none()
+        {
+            genetic_state.operators = Some(OperatorBandit::new(self.operator_selection));
+        }
 
-            genetic_state.pick_population(self.population_size);
+        self.print_progress(genetic_state.generation, best_route_len);
+
+        while genetic_state.generation < self.iterations {
+            match self.replacement {
+                Replacement::Generational => {
+                    let parents = genetic_state.parents_selection(self.pair_count)?;
+                    let variations = genetic_state.select_variations(
+                        self.variation(genetic_state.mutation_prob),
+                        parents.len(),
+                    );
+
+                    genetic_state.cross_parents(
+                        &parents,
+                        self.crossing_prob,
+                        dimension,
+                        problem,
+                        &variations,
+                        pool,
+                        self.memetic_fraction,
+                    )?;
+
+                    genetic_state.pick_population(self.population_size)?;
+                }
+                replacement => {
+                    for step in 0..self.pair_count.max(1) {
+                        let variation = genetic_state
+                            .select_variations(self.variation(genetic_state.mutation_prob), 1)[0];
+
+                        genetic_state.steady_state_step(
+                            self.crossing_prob,
+                            problem,
+                            &variation,
+                            genetic_state::is_enhanced(step, self.memetic_fraction),
+                            replacement,
+                            self.population_size,
+                        )?;
+                    }
+                }
+            }
 
-            let curr_best_route_len = genetic_state.elites.lock().unwrap()[0].get_route_len();
+            genetic_state.generation += 1;
+
+            if self.self_adaptive {
+                adaptation.extend(genetic_state.adaptation_stats());
+            }
+
+            if let Some(bandit) = &genetic_state.operators {
+                operators.push(bandit.stats(genetic_state.generation));
+            }
+
+            let curr_best_route_len = genetic_state.elites[0].get_route_len();
 
             if curr_best_route_len < best_route_len {
                 best_route_len = curr_best_route_len;
-                stagnation_iter = self.stagnation_iter;
-
-                let curr_prd = prd(best_route_len, self.opt);
+                genetic_state.stagnation_iter = self.stagnation_iter;
 
-                println!(
-                    "{}\t {}\t {:.2}%",
-                    i + 1,
-                    best_route_len,
-                    curr_prd
-                );
+                self.print_progress(genetic_state.generation, best_route_len);
 
-                if curr_prd == 0.0 {
+                if self.opt > 0 && prd(best_route_len, self.opt) == 0.0 {
                     break;
                 }
 
-                if mutation_multiplier > 1 {
-                    mutation_multiplier = 1;
-                    curr_mutation_prob = self.mutation_prob;
+                if genetic_state.mutation_multiplier > 1 {
+                    genetic_state.mutation_multiplier = 1;
+                    genetic_state.mutation_prob = self.mutation_prob;
                 }
             }
 
-            if stagnation_iter > 0 {
-                stagnation_iter -= 1;
+            if genetic_state.stagnation_iter > 0 {
+                genetic_state.stagnation_iter -= 1;
             }
 
-            if stagnation_iter == 0 {
-                if mutation_multiplier < self.max_mutation_multiply {
-                    mutation_multiplier += 1;
+            if genetic_state.stagnation_iter == 0 {
+                if genetic_state.mutation_multiplier < self.max_mutation_multiply {
+                    genetic_state.mutation_multiplier += 1;
 
-                    curr_mutation_prob *= self.mutation_prob * 100.0;
+                    genetic_state.mutation_prob *= self.mutation_prob * 100.0;
                 }
 
-                genetic_state.gen_random_population(self.population_size, tsp);
+                genetic_state.gen_random_population(self.population_size, problem)?;
 
-                stagnation_iter = self.stagnation_iter;
+                if self.self_adaptive {
+                    genetic_state.assign_strategies(self.mutation_prob);
+                }
+
+                genetic_state.stagnation_iter = self.stagnation_iter;
+            }
+
+            if let Some(path) = &self.checkpoint_path {
+                if genetic_state
+                    .generation
+                    .is_multiple_of(self.checkpoint_interval)
+                {
+                    checkpoint::save(&genetic_state, path)?;
+                }
             }
         }
 
-        let elites = genetic_state.elites.lock().unwrap();
+        let stats = RunStats {
+            generations: genetic_state.generation,
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            adaptation,
+            operators,
+        };
 
-        elites[0].get_route().clone()
+        Ok((genetic_state.elites[0].get_route().clone(), stats))
+    }
+}
+
+impl TspHeuristic for Genetic {
+    fn get_route(&self, tsp: &Tsp) -> Vec<usize> {
+        self.try_solve(tsp).expect("genetic algorithm failed")
     }
 }
 
@@ -242,31 +582,40 @@ impl TspHeuristic for Genetic {
 mod tests {
     use super::*;
 
-    use tsp_parser::TspParser;
+    use crate::generator::{Generator, Layout};
+    use crate::instance::{Instance, Metric};
 
     #[test]
     fn population_generates_correctly() {
-        let tsp = TspParser::from_file("test_files/berlin52.tsp").unwrap();
+        let instance = Generator::new(Layout::Uniform, 52, 1).instance().unwrap();
 
         let genetic = Genetic::new(7542, 1000, 100, 5, 50, 1.0, 0.02, 5000, 4, 1, 1.0);
 
         let GeneticState {
             population, elites, ..
-        } = genetic.initalize_genetic_state(&tsp);
+        } = std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, genetic.threads);
+
+            genetic.initalize_genetic_state(&instance, &pool).unwrap()
+        });
 
-        assert_eq!(100, population.lock().unwrap().len());
-        assert_eq!(5, elites.lock().unwrap().len());
+        assert_eq!(100, population.len());
+        assert_eq!(5, elites.len());
     }
 
     #[test]
     fn parents_generate_correctly() {
-        let tsp = TspParser::from_file("test_files/berlin52.tsp").unwrap();
+        let instance = Generator::new(Layout::Uniform, 52, 1).instance().unwrap();
 
         let genetic = Genetic::new(7542, 1000, 100, 5, 50, 1.0, 0.02, 5000, 4, 1, 1.0);
 
-        let genetic_state = genetic.initalize_genetic_state(&tsp);
+        let mut genetic_state = std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, genetic.threads);
 
-        let parents = genetic_state.parents_selection(genetic.pair_count);
+            genetic.initalize_genetic_state(&instance, &pool).unwrap()
+        });
+
+        let parents = genetic_state.parents_selection(genetic.pair_count).unwrap();
 
         assert_eq!(genetic.pair_count, parents.len());
 
@@ -277,28 +626,31 @@ mod tests {
 
     #[test]
     fn kid_cross_correctly() {
-        let tsp = TspParser::from_file("test_files/berlin52.tsp").unwrap();
+        let instance = Generator::new(Layout::Uniform, 52, 1).instance().unwrap();
 
-        let first_index = tsp.get_dimension() / 3;
+        let first_index = instance.dimension() / 3;
         let second_index = first_index * 2;
 
+        let mut rng = GeneticRng::from_entropy();
+
         for _ in 0..10 {
-            let first_parent = PopulationMember::gen_random(&tsp);
-            let second_parent = PopulationMember::gen_random(&tsp);
+            let first_parent = PopulationMember::gen_random(&instance, &mut rng).unwrap();
+            let second_parent = PopulationMember::gen_random(&instance, &mut rng).unwrap();
 
             let kid = GeneticState::cross_kid(
                 &first_parent,
                 &second_parent,
-                first_index,
-                second_index,
-                &tsp,
-                0.0,
+                first_index..second_index,
+                &instance,
+                &Variation::new(Crossover::Pmx, Mutation::Auto, 0.0),
                 false,
-            );
+                &mut rng,
+            )
+            .unwrap();
 
-            let mut cities = vec![false; tsp.get_dimension()];
+            let mut cities = vec![false; instance.dimension()];
 
-            for i in 0..tsp.get_dimension() {
+            for i in 0..instance.dimension() {
                 cities[kid.get_route()[i]] = true;
             }
 
@@ -310,27 +662,32 @@ mod tests {
 
     #[test]
     fn kid_cross_correctly_smaller() {
-        let tsp = TspParser::from_file("test_files/br17.atsp").unwrap();
+        let instance = Generator::new(Layout::Asymmetric, 17, 1)
+            .instance()
+            .unwrap();
 
-        let first_index = tsp.get_dimension() / 3;
+        let first_index = instance.dimension() / 3;
         let second_index = first_index * 2;
 
-        let first_parent = PopulationMember::gen_random(&tsp);
-        let second_parent = PopulationMember::gen_random(&tsp);
+        let mut rng = GeneticRng::from_entropy();
+
+        let first_parent = PopulationMember::gen_random(&instance, &mut rng).unwrap();
+        let second_parent = PopulationMember::gen_random(&instance, &mut rng).unwrap();
 
         let kid = GeneticState::cross_kid(
             &first_parent,
             &second_parent,
-            first_index,
-            second_index,
-            &tsp,
-            0.0,
+            first_index..second_index,
+            &instance,
+            &Variation::new(Crossover::Order, Mutation::Insert, 0.0),
             false,
-        );
+            &mut rng,
+        )
+        .unwrap();
 
-        let mut cities = vec![false; tsp.get_dimension()];
+        let mut cities = vec![false; instance.dimension()];
 
-        for i in 0..tsp.get_dimension() {
+        for i in 0..instance.dimension() {
             cities[kid.get_route()[i]] = true;
         }
 
@@ -341,37 +698,350 @@ mod tests {
 
     #[test]
     fn population_picked_correctly() {
-        let tsp = TspParser::from_file("test_files/berlin52.tsp").unwrap();
+        let instance = Generator::new(Layout::Uniform, 52, 1).instance().unwrap();
 
         let genetic = Genetic::new(7542, 1000, 100, 5, 50, 1.0, 0.02, 5000, 4, 1, 1.0);
 
-        let mut genetic_state = genetic.initalize_genetic_state(&tsp);
+        let mut genetic_state = std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, genetic.threads);
 
-        let parents = genetic_state.parents_selection(genetic.pair_count);
+            let mut genetic_state = genetic.initalize_genetic_state(&instance, &pool).unwrap();
 
-        genetic_state.cross_parents(
-            &parents,
-            genetic.crossing_prob,
-            tsp.get_dimension(),
-            &tsp,
-            genetic.mutation_prob,
-            1,
-            1.0,
-        );
+            let parents = genetic_state.parents_selection(genetic.pair_count).unwrap();
+
+            genetic_state
+                .cross_parents(
+                    &parents,
+                    genetic.crossing_prob,
+                    instance.dimension(),
+                    &instance,
+                    &vec![genetic.variation(genetic.mutation_prob); parents.len()],
+                    &pool,
+                    1.0,
+                )
+                .unwrap();
+
+            genetic_state
+        });
+
+        genetic_state
+            .pick_population(genetic.population_size)
+            .unwrap();
+
+        assert_eq!(genetic.population_size, genetic_state.population.len());
+        assert_eq!(genetic.elites_count, genetic_state.elites.len());
+
+        for elite in &genetic_state.elites {
+            assert!(genetic_state.population.contains(elite));
+        }
+    }
+
+    #[test]
+    fn work_split_correctly() {
+        for items in 0..20 {
+            for threads in 1..10 {
+                let work = worker_pool::split_work(items, threads);
+
+                assert!(work.len() <= threads);
+                assert_eq!(items, work.iter().map(|range| range.len()).sum::<usize>());
+
+                for range in &work {
+                    assert!(!range.is_empty());
+                    assert!(range.len() <= items / work.len() + 1);
+                }
+            }
+        }
+    }
 
-        genetic_state.pick_population(genetic.population_size);
+    fn asymmetric_instance(dimension: usize) -> Instance {
+        let mut rng = GeneticRng::seed_from_u64(dimension as u64);
+
+        let matrix = (0..dimension)
+            .map(|i| {
+                (0..dimension)
+                    .map(|j| if i == j { 0 } else { rng.gen_range(1..1000) })
+                    .collect()
+            })
+            .collect();
+
+        Instance::from_matrix(matrix).unwrap()
+    }
+
+    #[test]
+    fn parents_crossed_with_more_threads_than_pairs() {
+        let instance = asymmetric_instance(17);
+
+        let genetic = Genetic::new(39, 100, 10, 2, 3, 1.0, 0.02, 20, 4, 8, 0.1);
+
+        let genetic_state = std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, genetic.threads);
+
+            let mut genetic_state = genetic.initalize_genetic_state(&instance, &pool).unwrap();
+
+            let parents = genetic_state.parents_selection(genetic.pair_count).unwrap();
+
+            genetic_state
+                .cross_parents(
+                    &parents,
+                    genetic.crossing_prob,
+                    instance.dimension(),
+                    &instance,
+                    &vec![genetic.variation(genetic.mutation_prob); parents.len()],
+                    &pool,
+                    genetic.memetic_fraction,
+                )
+                .unwrap();
+
+            genetic_state
+        });
+
+        assert!(genetic_state.population.len() >= genetic.population_size);
+        assert_eq!(genetic.elites_count, genetic_state.elites.len());
+    }
+
+    #[test]
+    fn invalid_configuration_rejected() {
+        let result = Genetic::try_new(39, 100, 10, 0, 5, 1.0, 0.02, 20, 4, 1, 0.1);
+
+        assert!(matches!(result, Err(GeneticError::InvalidConfiguration(_))));
+
+        let result = Genetic::try_new(39, 100, 1, 1, 5, 1.0, 0.02, 20, 4, 1, 0.1);
+
+        assert!(matches!(result, Err(GeneticError::InvalidConfiguration(_))));
+
+        let result = Genetic::new(39, 100, 10, 2, 5, 1.0, 0.02, 20, 4, 1, 0.1)
+            .with_checkpoint("checkpoint.json", 0)
+            .validated();
+
+        assert!(matches!(result, Err(GeneticError::InvalidConfiguration(_))));
+    }
+
+    #[test]
+    fn invalid_route_rejected() {
+        let instance = asymmetric_instance(17);
+
+        let route = vec![0; instance.dimension()];
 
         assert_eq!(
-            genetic.population_size,
-            genetic_state.population.lock().unwrap().len()
+            Some(GeneticError::InvalidRoute),
+            PopulationMember::try_new(route, &instance).err()
         );
+    }
+
+    struct LineProblem {
+        dimension: usize,
+    }
+
+    impl PermutationProblem for LineProblem {
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        fn is_symmetric(&self) -> bool {
+            true
+        }
+
+        fn evaluate(&self, route: &[usize]) -> Option<u32> {
+            let mut route_len = 0;
+
+            for i in 0..route.len() {
+                let next_city = route[(i + 1) % route.len()];
+
+                route_len += (route[i] as i64 - next_city as i64).unsigned_abs() as u32;
+            }
+
+            Some(route_len)
+        }
+    }
+
+    #[test]
+    fn custom_problem_solved() {
+        let problem = LineProblem { dimension: 12 };
+
+        let genetic = Genetic::new(22, 100, 10, 3, 5, 0.9, 0.02, 20, 4, 2, 0.5);
+
+        let route = genetic.try_solve(&problem).unwrap();
+
+        let mut cities = route.clone();
+        cities.sort();
+
+        assert_eq!((0..12).collect::<Vec<_>>(), cities);
+        assert_eq!(Some(22), problem.evaluate(&route));
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let coordinates = (0..20)
+            .map(|i| ((i * 37 % 101) as f64, (i * 73 % 89) as f64))
+            .collect::<Vec<_>>();
+
+        let instance = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+
+        let checkpoint_path = std::env::temp_dir()
+            .join(format!("genetyk-checkpoint-{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
+        let genetic = |iterations| Genetic::new(0, iterations, 30, 5, 15, 0.9, 0.05, 6, 4, 2, 0.2);
+
+        let uninterrupted = genetic(30).with_seed(42).try_solve(&instance).unwrap();
+
+        genetic(12)
+            .with_seed(42)
+            .with_checkpoint(&checkpoint_path, 4)
+            .try_solve(&instance)
+            .unwrap();
+
+        let resumed = genetic(30).try_resume(&instance, &checkpoint_path).unwrap();
+
+        std::fs::remove_file(&checkpoint_path).ok();
+
+        assert_eq!(uninterrupted, resumed);
+
+        let other_instance = Instance::from_coordinates(&coordinates[..10], Metric::Euclidean);
+
+        genetic(4)
+            .with_seed(7)
+            .with_checkpoint(&checkpoint_path, 4)
+            .try_solve(&instance)
+            .unwrap();
+
+        assert!(matches!(
+            genetic(8).try_resume(&other_instance, &checkpoint_path),
+            Err(GeneticError::InvalidCheckpoint(_))
+        ));
+
+        std::fs::remove_file(&checkpoint_path).ok();
+    }
+
+    #[test]
+    fn initial_routes_seed_population() {
+        let problem = LineProblem { dimension: 12 };
+
+        let optimal_route = (0..12).collect::<Vec<_>>();
+
+        let genetic = Genetic::new(0, 0, 10, 3, 5, 0.9, 0.02, 20, 4, 2, 0.0)
+            .with_initial_routes(vec![optimal_route.clone()]);
+
+        assert_eq!(optimal_route, genetic.try_solve(&problem).unwrap());
+
+        let genetic = Genetic::new(0, 0, 10, 3, 5, 0.9, 0.02, 20, 4, 2, 0.0)
+            .with_initial_routes(vec![vec![0, 1, 2]]);
+
         assert_eq!(
-            genetic.elites_count,
-            genetic_state.elites.lock().unwrap().len()
+            Some(GeneticError::InvalidRoute),
+            genetic.try_solve(&problem).err()
         );
+    }
+
+    #[test]
+    fn self_adaptive_strategies_reported_per_generation() {
+        let instance = Generator::new(Layout::Uniform, 20, 3).instance().unwrap();
+
+        let (_, stats) = Genetic::new(0, 15, 30, 5, 15, 0.9, 0.05, 100, 4, 2, 0.2)
+            .with_self_adaptation(true)
+            .with_seed(11)
+            .try_solve_with_stats(&instance)
+            .unwrap();
+
+        assert_eq!(stats.generations, stats.adaptation.len());
+
+        for (generation, adaptation) in stats.adaptation.iter().enumerate() {
+            let members = adaptation
+                .crossover_counts
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>();
+
+            assert_eq!(generation + 1, adaptation.generation);
+            assert_eq!(30, members);
+            assert!(adaptation.min_mutation_prob <= adaptation.mean_mutation_prob);
+            assert!(adaptation.mean_mutation_prob <= adaptation.max_mutation_prob);
+        }
+
+        let (_, stats) = Genetic::new(0, 15, 30, 5, 15, 0.9, 0.05, 100, 4, 2, 0.2)
+            .try_solve_with_stats(&instance)
+            .unwrap();
+
+        assert!(stats.adaptation.is_empty());
+    }
+
+    #[test]
+    fn operator_usage_and_credit_reported_per_generation() {
+        let instance = Generator::new(Layout::Clustered, 20, 5).instance().unwrap();
+
+        let genetic = || Genetic::new(0, 12, 30, 5, 15, 0.9, 0.05, 100, 4, 2, 0.2);
+
+        let (_, stats) = genetic()
+            .with_operator_selection(OperatorSelection::Ucb)
+            .with_seed(3)
+            .try_solve_with_stats(&instance)
+            .unwrap();
+
+        assert_eq!(stats.generations, stats.operators.len());
+
+        for (generation, operators) in stats.operators.iter().enumerate() {
+            let uses = operators
+                .crossovers
+                .iter()
+                .map(|arm| arm.uses)
+                .sum::<usize>();
+
+            assert_eq!(15 * (generation + 1), uses);
+            assert_eq!(3, operators.mutations.len());
+        }
+
+        assert!(matches!(
+            genetic()
+                .with_self_adaptation(true)
+                .with_operator_selection(OperatorSelection::Matching)
+                .try_solve(&instance),
+            Err(GeneticError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn steady_state_replaces_members_in_place() {
+        let instance = Generator::new(Layout::Uniform, 25, 8).instance().unwrap();
+
+        for replacement in [Replacement::Worst, Replacement::Tournament] {
+            let genetic = Genetic::new(0, 10, 30, 5, 15, 0.9, 0.05, 100, 4, 2, 0.2)
+                .with_replacement(replacement)
+                .with_seed(4);
+
+            let mut genetic_state = std::thread::scope(|scope| {
+                let pool = WorkerPool::new(scope, genetic.threads);
+
+                genetic.initalize_genetic_state(&instance, &pool).unwrap()
+            });
+
+            let best_route_len = genetic_state.elites[0].get_route_len();
+
+            for step in 0..50 {
+                genetic_state
+                    .steady_state_step(
+                        genetic.crossing_prob,
+                        &instance,
+                        &genetic.variation(genetic.mutation_prob),
+                        genetic_state::is_enhanced(step, genetic.memetic_fraction),
+                        replacement,
+                        genetic.population_size,
+                    )
+                    .unwrap();
+
+                assert_eq!(genetic.population_size, genetic_state.population.len());
+            }
+
+            assert!(genetic_state.elites[0].get_route_len() <= best_route_len);
+
+            for (elite, member) in genetic_state.elites.iter().zip(&genetic_state.population) {
+                assert_eq!(elite.get_route_len(), member.get_route_len());
+            }
+
+            let (route, stats) = genetic.try_solve_with_stats(&instance).unwrap();
 
-        for elite in &*genetic_state.elites.lock().unwrap() {
-            assert!(genetic_state.population.lock().unwrap().contains(elite));
+            assert_eq!(10, stats.generations);
+            assert!(is_permutation(&route, instance.dimension()));
         }
     }
 }
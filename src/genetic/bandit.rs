@@ -0,0 +1,250 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::{Crossover, GeneticError, GeneticRng, Mutation, Variation};
+
+const MUTATION_ARMS: [Mutation; 3] = [Mutation::Invert, Mutation::Swap, Mutation::Insert];
+const MIN_PROBABILITY: f64 = 0.05;
+const ADAPTATION_RATE: f64 = 0.1;
+const UCB_EXPLORATION: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatorSelection {
+    #[default]
+    Fixed,
+    Matching,
+    Ucb,
+}
+
+impl FromStr for OperatorSelection {
+    type Err = GeneticError;
+
+    fn from_str(selection: &str) -> Result<OperatorSelection, GeneticError> {
+        match selection.to_ascii_lowercase().as_str() {
+            "fixed" => Ok(OperatorSelection::Fixed),
+            "matching" | "probability-matching" => Ok(OperatorSelection::Matching),
+            "ucb" => Ok(OperatorSelection::Ucb),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "operator selection has to be one of fixed, matching or ucb",
+            )),
+        }
+    }
+}
+
+impl Display for OperatorSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperatorSelection::Fixed => write!(f, "fixed"),
+            OperatorSelection::Matching => write!(f, "matching"),
+            OperatorSelection::Ucb => write!(f, "ucb"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Origin {
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    pub parent_len: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArmStats<T> {
+    pub operator: T,
+    pub uses: usize,
+    pub credit: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperatorStats {
+    pub generation: usize,
+    pub crossovers: Vec<ArmStats<Crossover>>,
+    pub mutations: Vec<ArmStats<Mutation>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Bandit<T> {
+    arms: Vec<T>,
+    uses: Vec<usize>,
+    credit: Vec<f64>,
+}
+
+impl<T: Copy + PartialEq> Bandit<T> {
+    fn new(arms: &[T]) -> Self {
+        Bandit {
+            arms: arms.to_vec(),
+            uses: vec![0; arms.len()],
+            credit: vec![0.0; arms.len()],
+        }
+    }
+
+    fn select(&mut self, selection: OperatorSelection, rng: &mut GeneticRng) -> T {
+        let arm = match selection {
+            OperatorSelection::Ucb => self.upper_confidence_arm(),
+            _ => self.matching_arm(rng),
+        };
+
+        self.uses[arm] += 1;
+
+        self.arms[arm]
+    }
+
+    fn matching_arm(&self, rng: &mut GeneticRng) -> usize {
+        let credit_sum = self.credit.iter().sum::<f64>();
+        let spread = 1.0 - self.arms.len() as f64 * MIN_PROBABILITY;
+
+        let mut random_value = rng.gen::<f64>();
+
+        for (arm, credit) in self.credit.iter().enumerate() {
+            let probability = if credit_sum > 0.0 {
+                MIN_PROBABILITY + spread * credit / credit_sum
+            } else {
+                1.0 / self.arms.len() as f64
+            };
+
+            random_value -= probability;
+
+            if random_value < 0.0 {
+                return arm;
+            }
+        }
+
+        self.arms.len() - 1
+    }
+
+    fn upper_confidence_arm(&self) -> usize {
+        if let Some(unused) = self.uses.iter().position(|&uses| uses == 0) {
+            return unused;
+        }
+
+        let total_uses = self.uses.iter().sum::<usize>() as f64;
+        let max_credit = self.credit.iter().copied().fold(0.0, f64::max);
+
+        let score = |arm: usize| {
+            let exploitation = if max_credit > 0.0 {
+                self.credit[arm] / max_credit
+            } else {
+                0.0
+            };
+
+            let exploration = (2.0 * total_uses.ln() / self.uses[arm] as f64).sqrt();
+
+            exploitation + UCB_EXPLORATION * exploration
+        };
+
+        (0..self.arms.len())
+            .max_by(|&first, &second| score(first).total_cmp(&score(second)))
+            .unwrap_or(0)
+    }
+
+    fn reward(&mut self, operator: T, reward: f64) {
+        if let Some(arm) = self.arms.iter().position(|&arm| arm == operator) {
+            self.credit[arm] += ADAPTATION_RATE * (reward - self.credit[arm]);
+        }
+    }
+
+    fn stats(&self) -> Vec<ArmStats<T>> {
+        (0..self.arms.len())
+            .map(|arm| ArmStats {
+                operator: self.arms[arm],
+                uses: self.uses[arm],
+                credit: self.credit[arm],
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorBandit {
+    selection: OperatorSelection,
+    crossovers: Bandit<Crossover>,
+    mutations: Bandit<Mutation>,
+}
+
+impl OperatorBandit {
+    pub fn new(selection: OperatorSelection) -> Self {
+        OperatorBandit {
+            selection,
+            crossovers: Bandit::new(&Crossover::ALL),
+            mutations: Bandit::new(&MUTATION_ARMS),
+        }
+    }
+
+    pub fn select(&mut self, variation: Variation, rng: &mut GeneticRng) -> Variation {
+        Variation {
+            crossover: self.crossovers.select(self.selection, rng),
+            mutation: self.mutations.select(self.selection, rng),
+            ..variation
+        }
+    }
+
+    pub fn credit(&mut self, origin: Origin, route_len: u32) {
+        let improvement = origin.parent_len.saturating_sub(route_len) as f64;
+        let reward = improvement / origin.parent_len.max(1) as f64;
+
+        self.crossovers.reward(origin.crossover, reward);
+        self.mutations.reward(origin.mutation, reward);
+    }
+
+    pub fn stats(&self, generation: usize) -> OperatorStats {
+        OperatorStats {
+            generation,
+            crossovers: self.crossovers.stats(),
+            mutations: self.mutations.stats(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewarded_operator_chosen_more_often() {
+        for selection in [OperatorSelection::Matching, OperatorSelection::Ucb] {
+            let mut bandit = OperatorBandit::new(selection);
+            let mut rng = GeneticRng::seed_from_u64(5);
+
+            let variation = Variation::new(Crossover::Pmx, Mutation::Auto, 0.02);
+
+            for _ in 0..500 {
+                let chosen = bandit.select(variation, &mut rng);
+
+                let route_len = if chosen.mutation == Mutation::Insert {
+                    90
+                } else {
+                    100
+                };
+
+                let origin = Origin {
+                    crossover: chosen.crossover,
+                    mutation: chosen.mutation,
+                    parent_len: 100,
+                };
+
+                bandit.credit(origin, route_len);
+            }
+
+            let stats = bandit.stats(1);
+            let insert = &stats.mutations[2];
+
+            assert_eq!(Mutation::Insert, insert.operator);
+            assert_eq!(
+                500,
+                stats.crossovers.iter().map(|arm| arm.uses).sum::<usize>()
+            );
+            assert!(insert.uses > 300);
+            assert!(insert.credit > stats.mutations[0].credit);
+        }
+
+        assert_eq!(
+            Ok(OperatorSelection::Matching),
+            "probability-matching".parse()
+        );
+        assert!("greedy".parse::<OperatorSelection>().is_err());
+    }
+}
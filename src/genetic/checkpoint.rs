@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::genetic_state::GeneticState;
+use crate::genetic::GeneticError;
+
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    version: u32,
+    state: &'a GeneticState,
+}
+
+#[derive(Deserialize)]
+struct Checkpoint {
+    version: u32,
+    state: GeneticState,
+}
+
+pub fn save(genetic_state: &GeneticState, path: &str) -> Result<(), GeneticError> {
+    let checkpoint = CheckpointRef {
+        version: CHECKPOINT_VERSION,
+        state: genetic_state,
+    };
+
+    let json = serde_json::to_string(&checkpoint)
+        .map_err(|err| GeneticError::InvalidCheckpoint(err.to_string()))?;
+
+    let temporary_path = format!("{}.tmp", path);
+
+    std::fs::write(&temporary_path, json)
+        .and_then(|_| std::fs::rename(&temporary_path, path))
+        .map_err(|err| GeneticError::InvalidCheckpoint(format!("{}: {}", path, err)))
+}
+
+pub fn load(path: &str) -> Result<GeneticState, GeneticError> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|err| GeneticError::InvalidCheckpoint(format!("{}: {}", path, err)))?;
+
+    let checkpoint: Checkpoint = serde_json::from_str(&json)
+        .map_err(|err| GeneticError::InvalidCheckpoint(format!("{}: {}", path, err)))?;
+
+    if checkpoint.version != CHECKPOINT_VERSION {
+        return Err(GeneticError::InvalidCheckpoint(format!(
+            "unsupported checkpoint version {}, expected {}",
+            checkpoint.version, CHECKPOINT_VERSION
+        )));
+    }
+
+    Ok(checkpoint.state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::prelude::*;
+
+    use crate::genetic::population_member::PopulationMember;
+    use crate::genetic::GeneticRng;
+    use crate::test_utils::line_instance;
+
+    #[test]
+    fn floats_restored_exactly() {
+        let instance = line_instance(30);
+
+        let mut rng = GeneticRng::seed_from_u64(3);
+        let mut genetic_state = GeneticState::new(5, 10, 1.0 / 3.0, rng.clone());
+
+        for _ in 0..50 {
+            let member = PopulationMember::gen_random(&instance, &mut rng).unwrap();
+
+            genetic_state.insert_member(member, 5);
+        }
+
+        let path = std::env::temp_dir()
+            .join(format!("genetyk-floats-{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+
+        let edge_values = [
+            f64::MIN_POSITIVE / 2.0,
+            5e-324,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            0.1 + 0.2,
+            1.2345678901234567,
+            1.0 / 3.0,
+        ];
+
+        for value in edge_values {
+            genetic_state.mutation_prob = value;
+
+            save(&genetic_state, &path).unwrap();
+
+            let saved = std::fs::read_to_string(&path).unwrap();
+            let loaded = load(&path).unwrap();
+
+            assert_eq!(value.to_bits(), loaded.mutation_prob.to_bits());
+
+            for (member, loaded_member) in genetic_state.population.iter().zip(&loaded.population) {
+                assert_eq!(
+                    member.get_chance().to_bits(),
+                    loaded_member.get_chance().to_bits()
+                );
+            }
+
+            save(&loaded, &path).unwrap();
+
+            assert_eq!(saved, std::fs::read_to_string(&path).unwrap());
+        }
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneticError {
+    InvalidConfiguration(&'static str),
+    InstanceTooSmall(usize),
+    InvalidInstance(&'static str),
+    InvalidFile(String),
+    InvalidRoute,
+    PopulationTooSmall,
+    NotEnoughDistinctRoutes(usize),
+    CrossoverFailed,
+    WorkerPanicked,
+    InvalidCheckpoint(String),
+}
+
+impl Display for GeneticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneticError::InvalidConfiguration(reason) => {
+                write!(f, "invalid configuration: {}", reason)
+            }
+            GeneticError::InstanceTooSmall(dimension) => {
+                write!(f, "instance with {} cities is too small", dimension)
+            }
+            GeneticError::InvalidInstance(reason) => write!(f, "invalid instance: {}", reason),
+            GeneticError::InvalidFile(reason) => write!(f, "invalid file: {}", reason),
+            GeneticError::InvalidRoute => write!(f, "route is not a permutation of the instance"),
+            GeneticError::PopulationTooSmall => {
+                write!(f, "population is too small to select parents")
+            }
+            GeneticError::NotEnoughDistinctRoutes(population_size) => write!(
+                f,
+                "couldn't generate {} members with distinct route lengths",
+                population_size
+            ),
+            GeneticError::CrossoverFailed => write!(f, "crossover produced an invalid route"),
+            GeneticError::WorkerPanicked => write!(f, "worker thread panicked"),
+            GeneticError::InvalidCheckpoint(reason) => write!(f, "invalid checkpoint: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GeneticError {}
@@ -1,284 +1,494 @@
-use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use std::collections::BTreeSet;
+use std::ops::Range;
 
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use tsp_parser::{neighbourhood, Tsp, TspType};
-
+use crate::genetic::bandit::{OperatorBandit, Origin};
+use crate::genetic::operators::{Crossover, Strategy, Variation};
 use crate::genetic::population_member::PopulationMember;
-use crate::genetic::Genetic;
+use crate::genetic::replacement::Replacement;
+use crate::genetic::worker_pool::{self, Job, WorkerPool};
+use crate::genetic::{AdaptationStats, Genetic, GeneticError, GeneticRng, MAX_DUPLICATE_ATTEMPTS};
+use crate::problem::PermutationProblem;
+
+const TOURNAMENT_SIZE: usize = 3;
 
+#[derive(Serialize, Deserialize)]
 pub struct GeneticState {
-    pub population: Arc<Mutex<HashSet<PopulationMember>>>,
-    pub elites: Arc<Mutex<Vec<PopulationMember>>>,
+    pub population: BTreeSet<PopulationMember>,
+    pub elites: Vec<PopulationMember>,
+    pub generation: usize,
+    pub stagnation_iter: usize,
+    pub mutation_multiplier: usize,
+    pub mutation_prob: f64,
+    pub rng: GeneticRng,
+    #[serde(default)]
+    pub operators: Option<OperatorBandit>,
 }
 
 impl GeneticState {
     pub fn new(
-        population: Arc<Mutex<HashSet<PopulationMember>>>,
-        elites: Arc<Mutex<Vec<PopulationMember>>>,
+        elites_count: usize,
+        stagnation_iter: usize,
+        mutation_prob: f64,
+        rng: GeneticRng,
     ) -> GeneticState {
-        GeneticState { population, elites }
+        GeneticState {
+            population: BTreeSet::new(),
+            elites: Vec::with_capacity(elites_count),
+            generation: 0,
+            stagnation_iter,
+            mutation_multiplier: 1,
+            mutation_prob,
+            rng,
+            operators: None,
+        }
+    }
+
+    pub fn merge_kids(&mut self, kids: Vec<PopulationMember>) {
+        for kid in kids {
+            if let (Some(bandit), Some(origin)) = (&mut self.operators, kid.get_origin()) {
+                bandit.credit(origin, kid.get_route_len());
+            }
+
+            if self.population.insert(kid.clone()) {
+                Genetic::insert_elite(&mut self.elites, kid);
+            }
+        }
+    }
+
+    pub fn insert_member(&mut self, member: PopulationMember, elites_count: usize) -> bool {
+        if !self.population.insert(member.clone()) {
+            return false;
+        }
+
+        if self.elites.len() < elites_count {
+            Genetic::insert_elite_begin(&mut self.elites, member);
+        } else {
+            Genetic::insert_elite(&mut self.elites, member);
+        }
+
+        true
+    }
+
+    pub fn fill_random<P: PermutationProblem>(
+        &mut self,
+        population_size: usize,
+        elites_count: usize,
+        problem: &P,
+    ) -> Result<(), GeneticError> {
+        let mut duplicates = 0;
+
+        while self.population.len() < population_size {
+            let member = PopulationMember::gen_random(problem, &mut self.rng)?;
+
+            if self.insert_member(member, elites_count) {
+                duplicates = 0;
+            } else {
+                duplicates += 1;
+
+                if duplicates > MAX_DUPLICATE_ATTEMPTS {
+                    return Err(GeneticError::NotEnoughDistinctRoutes(population_size));
+                }
+            }
+        }
+
+        Ok(())
     }
 
     pub fn parents_selection(
-        &self,
+        &mut self,
         pair_count: usize,
-    ) -> Vec<(PopulationMember, PopulationMember)> {
+    ) -> Result<Vec<(PopulationMember, PopulationMember)>, GeneticError> {
+        if self.population.len() < 2 {
+            return Err(GeneticError::PopulationTooSmall);
+        }
+
         let mut parents = Vec::with_capacity(pair_count);
 
-        let chances_sum = self
-            .population
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|p| p.get_chance())
-            .sum::<f64>();
+        let chances_sum = self.population.iter().map(|p| p.get_chance()).sum::<f64>();
 
         for _ in 0..pair_count {
-            let first_parent = self.select_parent(chances_sum);
-            let mut second_parent = self.select_parent(chances_sum);
+            let first_parent = self.select_parent(chances_sum)?;
+            let mut second_parent = self.select_parent(chances_sum)?;
 
             while second_parent == first_parent {
-                second_parent = self.select_parent(chances_sum);
+                second_parent = self.select_parent(chances_sum)?;
             }
 
             parents.push((first_parent, second_parent));
         }
 
-        parents
+        Ok(parents)
     }
 
-    fn select_parent(&self, chances_sum: f64) -> PopulationMember {
-        let mut random_value = thread_rng().gen::<f64>() * chances_sum;
+    fn select_parent(&mut self, chances_sum: f64) -> Result<PopulationMember, GeneticError> {
+        let mut random_value = self.rng.gen::<f64>() * chances_sum;
 
-        let curr_population = self.population.lock().unwrap();
-        let mut curr_population_member = curr_population.iter().next().unwrap();
+        let mut curr_population_member = self
+            .population
+            .iter()
+            .next()
+            .ok_or(GeneticError::PopulationTooSmall)?;
 
-        for population_member in &*curr_population {
+        for population_member in &self.population {
             random_value -= population_member.get_chance();
 
             if random_value < 0.0 {
-                return population_member.clone();
+                return Ok(population_member.clone());
             }
 
             curr_population_member = population_member;
         }
 
-        curr_population_member.clone()
+        Ok(curr_population_member.clone())
     }
 
-    pub fn cross_parents(
+    pub fn select_variations(&mut self, variation: Variation, count: usize) -> Vec<Variation> {
+        match &mut self.operators {
+            Some(bandit) => (0..count)
+                .map(|_| bandit.select(variation, &mut self.rng))
+                .collect(),
+            None => vec![variation; count],
+        }
+    }
+
+    pub fn cross_parents<'scope, P: PermutationProblem>(
         &mut self,
         parents: &[(PopulationMember, PopulationMember)],
         crossing_prob: f64,
         dimension: usize,
-        tsp: &Tsp,
-        mutation_prob: f64,
-        threads: usize,
+        problem: &'scope P,
+        variations: &[Variation],
+        pool: &WorkerPool<'scope>,
         memetic_fraction: f64,
-    ) {
-        let parents_per_thread = parents.len() / threads;
-        let parents_iter = parents.chunks(parents_per_thread);
-
-        let mut threads = Vec::with_capacity(threads);
-
-        for pairs in parents_iter {
-            let pairs_chunk = pairs.to_vec();
-            let tsp = tsp.clone();
-
-            let population_clone = Arc::clone(&self.population);
-            let elites_clone = Arc::clone(&self.elites);
-
-            threads.push(std::thread::spawn(move || {
-                for i in 0..pairs_chunk.len() {
-                    let pair = &pairs_chunk[i];
-
-                    let modulus = (1.0 / memetic_fraction).round() as usize;
-
-                    let (first_kid, second_kid) = if i % modulus == 0 {
-                        Self::cross_pair(pair, crossing_prob, dimension, &tsp, mutation_prob, true)
-                    } else {
-                        Self::cross_pair(pair, crossing_prob, dimension, &tsp, mutation_prob, false)
-                    };
-
-                    if let Some(ref kid) = first_kid {
-                        if population_clone.lock().unwrap().insert(kid.clone()) {
-                            Genetic::insert_elite(&mut elites_clone.lock().unwrap(), kid.clone());
-                        }
-                    }
-
-                    if let Some(ref kid) = second_kid {
-                        if population_clone.lock().unwrap().insert(kid.clone()) {
-                            Genetic::insert_elite(&mut elites_clone.lock().unwrap(), kid.clone());
-                        }
-                    }
+    ) -> Result<(), GeneticError> {
+        if variations.len() != parents.len() {
+            return Err(GeneticError::InvalidConfiguration(
+                "every parent pair needs its own variation",
+            ));
+        }
+
+        let work = worker_pool::split_work(parents.len(), pool.get_threads());
+
+        let mut jobs: Vec<Job<'scope>> = Vec::with_capacity(work.len());
+
+        for range in work {
+            let chunk_start = range.start;
+            let variations_chunk = variations[range.clone()].to_vec();
+            let pairs_chunk = parents[range].to_vec();
+            let seed = self.rng.gen::<u64>();
+
+            jobs.push(Box::new(move || {
+                let mut rng = GeneticRng::seed_from_u64(seed);
+                let mut kids = Vec::with_capacity(2 * pairs_chunk.len());
+
+                for (i, (pair, variation)) in pairs_chunk.iter().zip(&variations_chunk).enumerate()
+                {
+                    let enhance = is_enhanced(chunk_start + i, memetic_fraction);
+
+                    let (first_kid, second_kid) = Self::cross_pair(
+                        pair,
+                        crossing_prob,
+                        dimension,
+                        problem,
+                        variation,
+                        enhance,
+                        &mut rng,
+                    )?;
+
+                    let origin = kid_origin(pair, variation);
+
+                    kids.extend(first_kid.map(|kid| kid.with_origin(origin)));
+                    kids.extend(second_kid.map(|kid| kid.with_origin(origin)));
                 }
+
+                Ok(kids)
             }));
         }
 
-        for thread in threads {
-            thread.join().unwrap();
+        for kids in pool.run(jobs)? {
+            self.merge_kids(kids);
         }
+
+        Ok(())
     }
 
-    fn cross_pair(
+    pub fn steady_state_step<P: PermutationProblem>(
+        &mut self,
+        crossing_prob: f64,
+        problem: &P,
+        variation: &Variation,
+        enhance: bool,
+        replacement: Replacement,
+        population_size: usize,
+    ) -> Result<(), GeneticError> {
+        let pair = self
+            .parents_selection(1)?
+            .pop()
+            .ok_or(GeneticError::PopulationTooSmall)?;
+
+        let (first_kid, second_kid) = Self::cross_pair(
+            &pair,
+            crossing_prob,
+            problem.dimension(),
+            problem,
+            variation,
+            enhance,
+            &mut self.rng,
+        )?;
+
+        let origin = kid_origin(&pair, variation);
+
+        self.merge_kids(
+            first_kid
+                .into_iter()
+                .chain(second_kid)
+                .map(|kid| kid.with_origin(origin))
+                .collect(),
+        );
+
+        while self.population.len() > population_size.max(self.elites.len()) {
+            self.remove_victim(replacement);
+        }
+
+        Ok(())
+    }
+
+    fn remove_victim(&mut self, replacement: Replacement) {
+        let protected = self.elites.len();
+        let last = self.population.len() - 1;
+
+        let victim = match replacement {
+            Replacement::Tournament => (0..TOURNAMENT_SIZE)
+                .map(|_| self.rng.gen_range(protected..=last))
+                .max()
+                .unwrap_or(last),
+            _ => last,
+        };
+
+        if let Some(victim) = self.population.iter().nth(victim).cloned() {
+            self.population.remove(&victim);
+        }
+    }
+
+    pub fn cross_pair<P: PermutationProblem>(
         (first_parent, second_parent): &(PopulationMember, PopulationMember),
         crossing_prob: f64,
         dimension: usize,
-        tsp: &Tsp,
-        mutation_prob: f64,
+        problem: &P,
+        variation: &Variation,
         enhance: bool,
-    ) -> (Option<PopulationMember>, Option<PopulationMember>) {
+        rng: &mut GeneticRng,
+    ) -> Result<(Option<PopulationMember>, Option<PopulationMember>), GeneticError> {
         let mut first_kid = None;
         let mut second_kid = None;
 
         let first_index = dimension / 3;
         let second_index = first_index * 2;
 
-        if thread_rng().gen::<f64>() < crossing_prob {
+        if rng.gen::<f64>() < crossing_prob {
             first_kid = Some(GeneticState::cross_kid(
                 first_parent,
                 second_parent,
-                first_index,
-                second_index,
-                tsp,
-                mutation_prob,
+                first_index..second_index,
+                problem,
+                variation,
                 enhance,
-            ));
+                rng,
+            )?);
         }
 
-        if thread_rng().gen::<f64>() < crossing_prob {
+        if rng.gen::<f64>() < crossing_prob {
             second_kid = Some(GeneticState::cross_kid(
                 second_parent,
                 first_parent,
-                first_index,
-                second_index,
-                tsp,
-                mutation_prob,
+                first_index..second_index,
+                problem,
+                variation,
                 enhance,
-            ));
+                rng,
+            )?);
         }
 
-        (first_kid, second_kid)
+        Ok((first_kid, second_kid))
     }
 
-    pub fn cross_kid(
+    pub fn cross_kid<P: PermutationProblem>(
         first_parent: &PopulationMember,
         second_parent: &PopulationMember,
-        first_index: usize,
-        second_index: usize,
-        tsp: &Tsp,
-        mutation_prob: f64,
+        fragment: Range<usize>,
+        problem: &P,
+        variation: &Variation,
         enhance: bool,
-    ) -> PopulationMember {
-        let mut kid_route = first_parent.get_route().clone();
-
-        let fragment_len = second_index - first_index;
+        rng: &mut GeneticRng,
+    ) -> Result<PopulationMember, GeneticError> {
+        let dimension = problem.dimension();
+
+        if first_parent.get_route().len() != dimension
+            || second_parent.get_route().len() != dimension
+        {
+            return Err(GeneticError::InvalidRoute);
+        }
 
-        for i in 0..fragment_len {
-            kid_route[first_index + i] = second_parent.get_route()[first_index + i];
+        if fragment.start > fragment.end || fragment.end > dimension {
+            return Err(GeneticError::CrossoverFailed);
         }
 
-        let mut first_similarities = vec![0; tsp.get_dimension()];
-        let mut second_similarities = vec![0; tsp.get_dimension()];
+        let strategy = if variation.self_adaptive {
+            Some(Strategy::inherit(
+                first_parent
+                    .get_strategy()
+                    .unwrap_or(variation.get_strategy()),
+                second_parent
+                    .get_strategy()
+                    .unwrap_or(variation.get_strategy()),
+                rng,
+            ))
+        } else {
+            None
+        };
+
+        let Strategy {
+            crossover,
+            mutation_prob,
+        } = strategy.unwrap_or(variation.get_strategy());
 
-        for i in 0..fragment_len {
-            first_similarities[first_parent.get_route()[first_index + i]] += 1;
-            second_similarities[second_parent.get_route()[first_index + i]] += 1;
-        }
+        let mut kid_route = crossover.cross(
+            first_parent.get_route(),
+            second_parent.get_route(),
+            fragment,
+        )?;
 
-        let mut indexes_and_cities = vec![];
+        let movable = problem.movable_range();
 
-        for i in 0..fragment_len {
-            let city = first_parent.get_route()[first_index + i];
+        for i in movable.start..movable.end.saturating_sub(1) {
+            let mutation_chance = mutation_prob / problem.dimension() as f64;
 
-            if second_similarities[city] == 0 {
-                for i in (0..first_index).chain(second_index..tsp.get_dimension()) {
-                    if second_parent.get_route()[i] == city {
-                        indexes_and_cities.push((i, city));
-                        break;
-                    }
-                }
+            if mutation_chance > rng.gen() {
+                let second_index = rng.gen_range(i + 1..movable.end);
+
+                variation
+                    .mutation
+                    .apply(&mut kid_route, i, second_index, problem.is_symmetric());
             }
         }
 
-        indexes_and_cities.sort_by(|(index1, _), (index2, _)| index1.cmp(index2));
-
-        let mut iac_iter = indexes_and_cities.iter();
+        problem.mutate(&mut kid_route, mutation_prob, rng);
+        problem.repair(&mut kid_route);
 
-        for i in 0..fragment_len {
-            let city = &mut kid_route[first_index + i];
+        let kid = if enhance {
+            PopulationMember::try_new_enhanced(kid_route, problem)?
+        } else {
+            PopulationMember::try_new(kid_route, problem)?
+        };
 
-            if first_similarities[*city] == 0 {
-                let &(_, swap_city) = iac_iter.next().unwrap();
+        Ok(match strategy {
+            Some(strategy) => kid.with_strategy(strategy),
+            None => kid,
+        })
+    }
 
-                *city = swap_city;
-            }
-        }
+    pub fn assign_strategies(&mut self, mutation_prob: f64) {
+        let population = std::mem::take(&mut self.population)
+            .into_iter()
+            .map(|member| match member.get_strategy() {
+                Some(_) => member,
+                None => member.with_strategy(Strategy::random(mutation_prob, &mut self.rng)),
+            })
+            .collect::<BTreeSet<_>>();
+
+        self.elites = self
+            .elites
+            .iter()
+            .map(|elite| population.get(elite).unwrap_or(elite).clone())
+            .collect();
 
-        for i in 0..tsp.get_dimension() - 1 {
-            let mutation_chance = mutation_prob / tsp.get_dimension() as f64;
+        self.population = population;
+    }
 
-            if mutation_chance > thread_rng().gen() {
-                let second_index = thread_rng().gen_range(i + 1..tsp.get_dimension());
+    pub fn adaptation_stats(&self) -> Option<AdaptationStats> {
+        let strategies = self
+            .population
+            .iter()
+            .filter_map(|member| member.get_strategy())
+            .collect::<Vec<_>>();
 
-                match tsp.get_tsp_type() {
-                    TspType::Symmetric => neighbourhood::invert(&mut kid_route[i..=second_index]),
-                    TspType::Asymmetric => neighbourhood::swap(&mut kid_route[i..=second_index]),
-                }
-            }
+        if strategies.is_empty() {
+            return None;
         }
 
-        if enhance {
-            PopulationMember::new_enhanced(kid_route, tsp)
-        } else {
-            PopulationMember::new(kid_route, tsp)
-        }
+        let mutation_probs = strategies
+            .iter()
+            .map(|strategy| strategy.mutation_prob)
+            .collect::<Vec<_>>();
+
+        let crossover_counts = Crossover::ALL
+            .iter()
+            .map(|&crossover| {
+                let count = strategies
+                    .iter()
+                    .filter(|strategy| strategy.crossover == crossover)
+                    .count();
+
+                (crossover, count)
+            })
+            .collect();
+
+        Some(AdaptationStats {
+            generation: self.generation,
+            mean_mutation_prob: mutation_probs.iter().sum::<f64>() / mutation_probs.len() as f64,
+            min_mutation_prob: mutation_probs.iter().copied().fold(f64::INFINITY, f64::min),
+            max_mutation_prob: mutation_probs.iter().copied().fold(0.0, f64::max),
+            crossover_counts,
+        })
     }
 
-    pub fn pick_population(&mut self, population_size: usize) {
-        let mut new_population = HashSet::with_capacity(2 * population_size);
+    pub fn pick_population(&mut self, population_size: usize) -> Result<(), GeneticError> {
+        let mut new_population = BTreeSet::new();
 
-        for elite in &*self.elites.lock().unwrap() {
+        for elite in &self.elites {
             new_population.insert(elite.clone());
         }
 
-        let chances_sum = self
-            .population
-            .lock()
-            .unwrap()
-            .iter()
-            .map(|p| p.get_chance())
-            .sum::<f64>();
+        let chances_sum = self.population.iter().map(|p| p.get_chance()).sum::<f64>();
 
         while new_population.len() < population_size {
-            new_population.insert(self.select_parent(chances_sum));
+            new_population.insert(self.select_parent(chances_sum)?);
         }
 
-        *self.population.lock().unwrap() = new_population;
-    }
+        self.population = new_population;
 
-    pub fn gen_random_population(&mut self, population_size: usize, tsp: &Tsp) {
-        let mut new_population = HashSet::with_capacity(tsp.get_dimension());
-        let mut locked_elites = self.elites.lock().unwrap();
+        Ok(())
+    }
 
-        for elite in &*locked_elites {
-            new_population.insert(elite.clone());
-        }
+    pub fn gen_random_population<P: PermutationProblem>(
+        &mut self,
+        population_size: usize,
+        problem: &P,
+    ) -> Result<(), GeneticError> {
+        self.population = self.elites.iter().cloned().collect();
 
-        for _ in 0..population_size - locked_elites.len() {
-            let mut member = PopulationMember::gen_random(tsp);
+        self.fill_random(population_size, self.elites.len(), problem)
+    }
+}
 
-            while !new_population.insert(member.clone()) {
-                member = PopulationMember::gen_random(tsp);
-            }
+pub fn is_enhanced(index: usize, memetic_fraction: f64) -> bool {
+    let modulus = (1.0 / memetic_fraction).round() as usize;
 
-            if locked_elites[locked_elites.len() - 1].get_route_len() > member.get_route_len() {
-                Genetic::insert_elite(&mut locked_elites, member);
-            }
-        }
+    memetic_fraction > 0.0 && index.is_multiple_of(modulus)
+}
 
-        self.population = Arc::new(Mutex::new(new_population));
+fn kid_origin(
+    (first_parent, second_parent): &(PopulationMember, PopulationMember),
+    variation: &Variation,
+) -> Origin {
+    Origin {
+        crossover: variation.crossover,
+        mutation: variation.mutation,
+        parent_len: first_parent
+            .get_route_len()
+            .min(second_parent.get_route_len()),
     }
 }
@@ -0,0 +1,389 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use rand::prelude::*;
+
+use crate::genetic::genetic_state::GeneticState;
+use crate::genetic::population_member::PopulationMember;
+use crate::genetic::worker_pool::{self, Job, WorkerPool};
+use crate::genetic::{
+    Genetic, GeneticError, GeneticRng, OperatorSelection, Replacement, MAX_DUPLICATE_ATTEMPTS,
+};
+use crate::problem::MultiObjectiveProblem;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoMember {
+    pub route: Vec<usize>,
+    pub objectives: Vec<u32>,
+}
+
+impl ParetoMember {
+    pub fn try_new<P: MultiObjectiveProblem>(
+        route: Vec<usize>,
+        problem: &P,
+    ) -> Result<ParetoMember, GeneticError> {
+        let objectives = problem
+            .evaluate_objectives(&route)
+            .ok_or(GeneticError::InvalidRoute)?;
+
+        Ok(ParetoMember { route, objectives })
+    }
+
+    pub fn dominates(&self, other: &ParetoMember) -> bool {
+        dominates(&self.objectives, &other.objectives)
+    }
+}
+
+fn dominates(first: &[u32], second: &[u32]) -> bool {
+    first.iter().zip(second).all(|(a, b)| a <= b) && first != second
+}
+
+pub fn non_dominated_sort(members: &[ParetoMember]) -> Vec<Vec<usize>> {
+    let mut dominated_by = vec![0; members.len()];
+    let mut dominating = vec![vec![]; members.len()];
+
+    for i in 0..members.len() {
+        for j in i + 1..members.len() {
+            if members[i].dominates(&members[j]) {
+                dominating[i].push(j);
+                dominated_by[j] += 1;
+            } else if members[j].dominates(&members[i]) {
+                dominating[j].push(i);
+                dominated_by[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = vec![];
+
+    let mut front = (0..members.len())
+        .filter(|&i| dominated_by[i] == 0)
+        .collect::<Vec<_>>();
+
+    while !front.is_empty() {
+        let mut next_front = vec![];
+
+        for &i in &front {
+            for &j in &dominating[i] {
+                dominated_by[j] -= 1;
+
+                if dominated_by[j] == 0 {
+                    next_front.push(j);
+                }
+            }
+        }
+
+        fronts.push(front);
+        front = next_front;
+    }
+
+    fronts
+}
+
+pub fn crowding_distances(members: &[ParetoMember], front: &[usize]) -> Vec<f64> {
+    let mut distances = vec![0.0; front.len()];
+
+    if front.len() <= 2 {
+        return vec![f64::INFINITY; front.len()];
+    }
+
+    let objectives_count = members[front[0]].objectives.len();
+
+    for objective in 0..objectives_count {
+        let value = |k: usize| members[front[k]].objectives[objective];
+
+        let mut order = (0..front.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&k| value(k));
+
+        let min = value(order[0]);
+        let max = value(order[front.len() - 1]);
+
+        distances[order[0]] = f64::INFINITY;
+        distances[order[front.len() - 1]] = f64::INFINITY;
+
+        if max == min {
+            continue;
+        }
+
+        for k in 1..front.len() - 1 {
+            distances[order[k]] +=
+                (value(order[k + 1]) - value(order[k - 1])) as f64 / (max - min) as f64;
+        }
+    }
+
+    distances
+}
+
+struct RankedPopulation {
+    members: Vec<ParetoMember>,
+    ranks: Vec<usize>,
+    crowding: Vec<f64>,
+}
+
+impl RankedPopulation {
+    fn new(members: Vec<ParetoMember>) -> RankedPopulation {
+        let mut ranks = vec![0; members.len()];
+        let mut crowding = vec![0.0; members.len()];
+
+        for (rank, front) in non_dominated_sort(&members).iter().enumerate() {
+            let distances = crowding_distances(&members, front);
+
+            for (k, &i) in front.iter().enumerate() {
+                ranks[i] = rank;
+                crowding[i] = distances[k];
+            }
+        }
+
+        RankedPopulation {
+            members,
+            ranks,
+            crowding,
+        }
+    }
+
+    fn is_better(&self, first: usize, second: usize) -> bool {
+        self.ranks[first] < self.ranks[second]
+            || (self.ranks[first] == self.ranks[second]
+                && self.crowding[first] > self.crowding[second])
+    }
+
+    fn tournament(&self, rng: &mut GeneticRng) -> usize {
+        let first = rng.gen_range(0..self.members.len());
+        let second = rng.gen_range(0..self.members.len());
+
+        if self.is_better(second, first) {
+            second
+        } else {
+            first
+        }
+    }
+
+    fn parents_selection<P: MultiObjectiveProblem>(
+        &self,
+        pair_count: usize,
+        problem: &P,
+        rng: &mut GeneticRng,
+    ) -> Result<Vec<(PopulationMember, PopulationMember)>, GeneticError> {
+        if self.members.len() < 2 {
+            return Err(GeneticError::PopulationTooSmall);
+        }
+
+        let mut parents = Vec::with_capacity(pair_count);
+
+        for _ in 0..pair_count {
+            let first_parent = self.tournament(rng);
+            let mut second_parent = self.tournament(rng);
+
+            while second_parent == first_parent {
+                second_parent = self.tournament(rng);
+            }
+
+            parents.push((
+                PopulationMember::try_new(self.members[first_parent].route.clone(), problem)?,
+                PopulationMember::try_new(self.members[second_parent].route.clone(), problem)?,
+            ));
+        }
+
+        Ok(parents)
+    }
+
+    fn front(self) -> Vec<ParetoMember> {
+        let mut front = self
+            .members
+            .into_iter()
+            .zip(self.ranks)
+            .filter(|(_, rank)| *rank == 0)
+            .map(|(member, _)| member)
+            .collect::<Vec<_>>();
+
+        front.sort_by(|first, second| first.objectives.cmp(&second.objectives));
+        front.dedup_by(|first, second| first.objectives == second.objectives);
+
+        front
+    }
+}
+
+fn select_survivors(members: Vec<ParetoMember>, population_size: usize) -> Vec<ParetoMember> {
+    let mut selected = Vec::with_capacity(population_size);
+
+    for mut front in non_dominated_sort(&members) {
+        if selected.len() + front.len() > population_size {
+            let distances = crowding_distances(&members, &front);
+
+            let mut order = (0..front.len()).collect::<Vec<_>>();
+            order.sort_by(|&first, &second| distances[second].total_cmp(&distances[first]));
+
+            front = order
+                .into_iter()
+                .take(population_size - selected.len())
+                .map(|k| front[k])
+                .collect();
+        }
+
+        selected.extend(front);
+
+        if selected.len() == population_size {
+            break;
+        }
+    }
+
+    let mut members = members.into_iter().map(Some).collect::<Vec<_>>();
+
+    selected
+        .into_iter()
+        .filter_map(|i| members[i].take())
+        .collect()
+}
+
+impl Genetic {
+    pub fn try_solve_pareto<P: MultiObjectiveProblem>(
+        &self,
+        problem: &P,
+    ) -> Result<Vec<ParetoMember>, GeneticError> {
+        // Elites, stagnation and memetic search belong to the single-objective loop and are
+        // not used here, NSGA-II ranks the whole population by fronts and crowding instead.
+        self.validate()?;
+
+        if self.self_adaptive
+            || self.operator_selection != OperatorSelection::Fixed
+            || self.replacement != Replacement::Generational
+        {
+            return Err(GeneticError::InvalidConfiguration(
+                "NSGA-II supports neither adaptive operators nor steady-state replacement",
+            ));
+        }
+
+        if self.checkpoint_path.is_some() || !self.initial_routes.is_empty() {
+            return Err(GeneticError::InvalidConfiguration(
+                "NSGA-II supports neither checkpoints nor initial routes",
+            ));
+        }
+
+        if problem.dimension() < 2 {
+            return Err(GeneticError::InstanceTooSmall(problem.dimension()));
+        }
+
+        std::thread::scope(|scope| {
+            let pool = WorkerPool::new(scope, self.threads);
+
+            self.run_pareto_generations(problem, &pool)
+        })
+    }
+
+    fn random_pareto_population<P: MultiObjectiveProblem>(
+        &self,
+        problem: &P,
+        rng: &mut GeneticRng,
+    ) -> Result<Vec<ParetoMember>, GeneticError> {
+        let mut routes = HashSet::with_capacity(self.population_size);
+        let mut members = Vec::with_capacity(self.population_size);
+
+        let mut duplicates = 0;
+
+        while members.len() < self.population_size {
+            let route = PopulationMember::gen_random(problem, rng)?
+                .get_route()
+                .clone();
+
+            if routes.insert(route.clone()) {
+                members.push(ParetoMember::try_new(route, problem)?);
+                duplicates = 0;
+            } else {
+                duplicates += 1;
+
+                if duplicates > MAX_DUPLICATE_ATTEMPTS {
+                    return Err(GeneticError::NotEnoughDistinctRoutes(self.population_size));
+                }
+            }
+        }
+
+        Ok(members)
+    }
+
+    fn cross_pareto_parents<'scope, P: MultiObjectiveProblem>(
+        &self,
+        parents: &[(PopulationMember, PopulationMember)],
+        problem: &'scope P,
+        pool: &WorkerPool<'scope>,
+        rng: &mut GeneticRng,
+    ) -> Result<Vec<PopulationMember>, GeneticError> {
+        let dimension = problem.dimension();
+        let crossing_prob = self.crossing_prob;
+        let variation = self.variation(self.mutation_prob);
+
+        let work = worker_pool::split_work(parents.len(), pool.get_threads());
+
+        let mut jobs: Vec<Job<'scope>> = Vec::with_capacity(work.len());
+
+        for range in work {
+            let pairs_chunk = parents[range].to_vec();
+            let seed = rng.gen::<u64>();
+
+            jobs.push(Box::new(move || {
+                let mut rng = GeneticRng::seed_from_u64(seed);
+                let mut kids = Vec::with_capacity(2 * pairs_chunk.len());
+
+                for pair in &pairs_chunk {
+                    let (first_kid, second_kid) = GeneticState::cross_pair(
+                        pair,
+                        crossing_prob,
+                        dimension,
+                        problem,
+                        &variation,
+                        false,
+                        &mut rng,
+                    )?;
+
+                    kids.extend(first_kid);
+                    kids.extend(second_kid);
+                }
+
+                Ok(kids)
+            }));
+        }
+
+        Ok(pool.run(jobs)?.into_iter().flatten().collect())
+    }
+
+    fn run_pareto_generations<'scope, P: MultiObjectiveProblem>(
+        &self,
+        problem: &'scope P,
+        pool: &WorkerPool<'scope>,
+    ) -> Result<Vec<ParetoMember>, GeneticError> {
+        eprint!("Generatic initial population... ");
+        std::io::stderr().flush().ok();
+
+        let mut rng = self.new_rng();
+
+        let mut population =
+            RankedPopulation::new(self.random_pareto_population(problem, &mut rng)?);
+
+        eprintln!("Done");
+
+        for _ in 0..self.iterations {
+            let parents = population.parents_selection(self.pair_count, problem, &mut rng)?;
+            let kids = self.cross_pareto_parents(&parents, problem, pool, &mut rng)?;
+
+            let mut members = population.members;
+
+            let mut routes = members
+                .iter()
+                .map(|member| member.route.clone())
+                .collect::<HashSet<_>>();
+
+            for kid in kids {
+                if routes.insert(kid.get_route().clone()) {
+                    members.push(ParetoMember::try_new(kid.get_route().clone(), problem)?);
+                }
+            }
+
+            population = RankedPopulation::new(select_survivors(members, self.population_size));
+        }
+
+        let front = population.front();
+
+        eprintln!("Pareto front of {} routes", front.len());
+
+        Ok(front)
+    }
+}
@@ -0,0 +1,338 @@
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use tsp_parser::neighbourhood;
+
+use crate::genetic::{GeneticError, GeneticRng};
+use crate::problem;
+
+const LEARNING_RATE: f64 = 0.5;
+const INITIAL_SPREAD: f64 = 1.5;
+const CROSSOVER_SWITCH_PROB: f64 = 0.1;
+const MIN_MUTATION_PROB: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crossover {
+    Pmx,
+    Order,
+}
+
+impl FromStr for Crossover {
+    type Err = GeneticError;
+
+    fn from_str(crossover: &str) -> Result<Crossover, GeneticError> {
+        match crossover.to_ascii_lowercase().as_str() {
+            "pmx" => Ok(Crossover::Pmx),
+            "order" | "ox" => Ok(Crossover::Order),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "crossover has to be one of pmx or order",
+            )),
+        }
+    }
+}
+
+impl Display for Crossover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Crossover::Pmx => write!(f, "pmx"),
+            Crossover::Order => write!(f, "order"),
+        }
+    }
+}
+
+impl Crossover {
+    pub const ALL: [Crossover; 2] = [Crossover::Pmx, Crossover::Order];
+
+    pub fn cross(
+        self,
+        first_route: &[usize],
+        second_route: &[usize],
+        fragment: Range<usize>,
+    ) -> Result<Vec<usize>, GeneticError> {
+        match self {
+            Crossover::Pmx => pmx(first_route, second_route, fragment),
+            Crossover::Order => order(first_route, second_route, fragment),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mutation {
+    Auto,
+    Invert,
+    Swap,
+    Insert,
+}
+
+impl FromStr for Mutation {
+    type Err = GeneticError;
+
+    fn from_str(mutation: &str) -> Result<Mutation, GeneticError> {
+        match mutation.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Mutation::Auto),
+            "invert" => Ok(Mutation::Invert),
+            "swap" => Ok(Mutation::Swap),
+            "insert" => Ok(Mutation::Insert),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "mutation has to be one of auto, invert, swap or insert",
+            )),
+        }
+    }
+}
+
+impl Display for Mutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mutation::Auto => write!(f, "auto"),
+            Mutation::Invert => write!(f, "invert"),
+            Mutation::Swap => write!(f, "swap"),
+            Mutation::Insert => write!(f, "insert"),
+        }
+    }
+}
+
+impl Mutation {
+    pub fn apply(self, route: &mut [usize], i: usize, j: usize, symmetric: bool) {
+        let mutation = match self {
+            Mutation::Auto if symmetric => Mutation::Invert,
+            Mutation::Auto => Mutation::Swap,
+            mutation => mutation,
+        };
+
+        match mutation {
+            Mutation::Invert => neighbourhood::invert(&mut route[i..=j]),
+            Mutation::Insert => problem::insert(route, i, j),
+            _ => neighbourhood::swap(&mut route[i..=j]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Strategy {
+    pub crossover: Crossover,
+    pub mutation_prob: f64,
+}
+
+impl Strategy {
+    pub fn new(crossover: Crossover, mutation_prob: f64) -> Strategy {
+        Strategy {
+            crossover,
+            mutation_prob,
+        }
+    }
+
+    pub fn random(mutation_prob: f64, rng: &mut GeneticRng) -> Strategy {
+        let crossover = *Crossover::ALL.choose(rng).unwrap_or(&Crossover::Pmx);
+
+        Strategy::new(crossover, perturbed(mutation_prob, INITIAL_SPREAD, rng))
+    }
+
+    pub fn inherit(first: Strategy, second: Strategy, rng: &mut GeneticRng) -> Strategy {
+        let crossover = if rng.gen::<f64>() < CROSSOVER_SWITCH_PROB {
+            *Crossover::ALL.choose(rng).unwrap_or(&first.crossover)
+        } else {
+            first.crossover
+        };
+
+        let mutation_prob = (first.mutation_prob * second.mutation_prob).sqrt();
+
+        Strategy::new(crossover, perturbed(mutation_prob, LEARNING_RATE, rng))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variation {
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    pub mutation_prob: f64,
+    pub self_adaptive: bool,
+}
+
+impl Variation {
+    pub fn new(crossover: Crossover, mutation: Mutation, mutation_prob: f64) -> Variation {
+        Variation {
+            crossover,
+            mutation,
+            mutation_prob,
+            self_adaptive: false,
+        }
+    }
+
+    pub fn with_self_adaptation(mut self, self_adaptive: bool) -> Self {
+        self.self_adaptive = self_adaptive;
+        self
+    }
+
+    pub fn get_strategy(&self) -> Strategy {
+        Strategy::new(self.crossover, self.mutation_prob)
+    }
+}
+
+fn perturbed(mutation_prob: f64, spread: f64, rng: &mut GeneticRng) -> f64 {
+    let factor = (spread * rng.gen_range(-1.0..=1.0)).exp();
+
+    (mutation_prob.max(MIN_MUTATION_PROB) * factor).clamp(MIN_MUTATION_PROB, 1.0)
+}
+
+fn pmx(
+    first_route: &[usize],
+    second_route: &[usize],
+    fragment: Range<usize>,
+) -> Result<Vec<usize>, GeneticError> {
+    let dimension = first_route.len();
+
+    let Range {
+        start: first_index,
+        end: second_index,
+    } = fragment;
+
+    let mut kid_route = first_route.to_vec();
+
+    let fragment_len = second_index - first_index;
+
+    for i in 0..fragment_len {
+        kid_route[first_index + i] = second_route[first_index + i];
+    }
+
+    let mut first_similarities = vec![0; dimension];
+    let mut second_similarities = vec![0; dimension];
+
+    for i in 0..fragment_len {
+        first_similarities[first_route[first_index + i]] += 1;
+        second_similarities[second_route[first_index + i]] += 1;
+    }
+
+    let mut indexes_and_cities = vec![];
+
+    for i in 0..fragment_len {
+        let city = first_route[first_index + i];
+
+        if second_similarities[city] == 0 {
+            for i in (0..first_index).chain(second_index..dimension) {
+                if second_route[i] == city {
+                    indexes_and_cities.push((i, city));
+                    break;
+                }
+            }
+        }
+    }
+
+    indexes_and_cities.sort_by(|(index1, _), (index2, _)| index1.cmp(index2));
+
+    let mut iac_iter = indexes_and_cities.iter();
+
+    for i in 0..fragment_len {
+        let city = &mut kid_route[first_index + i];
+
+        if first_similarities[*city] == 0 {
+            let &(_, swap_city) = iac_iter.next().ok_or(GeneticError::CrossoverFailed)?;
+
+            *city = swap_city;
+        }
+    }
+
+    Ok(kid_route)
+}
+
+fn order(
+    first_route: &[usize],
+    second_route: &[usize],
+    fragment: Range<usize>,
+) -> Result<Vec<usize>, GeneticError> {
+    let dimension = first_route.len();
+
+    let mut kid_route = first_route.to_vec();
+    let mut taken = vec![false; dimension];
+
+    for i in fragment.clone() {
+        kid_route[i] = second_route[i];
+        taken[second_route[i]] = true;
+    }
+
+    let mut cities = first_route
+        .iter()
+        .cycle()
+        .skip(fragment.end)
+        .take(dimension)
+        .filter(|&&city| !taken[city]);
+
+    for i in (fragment.end..dimension).chain(0..fragment.start) {
+        kid_route[i] = *cities.next().ok_or(GeneticError::CrossoverFailed)?;
+    }
+
+    Ok(kid_route)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossovers_applied_correctly() {
+        let first_route = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let second_route = [8, 2, 6, 7, 1, 5, 4, 0, 3];
+
+        let kid_route = Crossover::Order
+            .cross(&first_route, &second_route, 3..6)
+            .unwrap();
+
+        assert_eq!(vec![2, 3, 4, 7, 1, 5, 6, 8, 0], kid_route);
+
+        let kid_route = Crossover::Pmx
+            .cross(&first_route, &second_route, 3..6)
+            .unwrap();
+
+        let mut cities = kid_route.clone();
+        cities.sort();
+
+        assert_eq!(first_route.to_vec(), cities);
+    }
+
+    #[test]
+    fn mutations_applied_correctly() {
+        let mut route = vec![0, 1, 2, 3, 4];
+
+        Mutation::Insert.apply(&mut route, 1, 3, true);
+        assert_eq!(vec![0, 2, 3, 1, 4], route);
+
+        Mutation::Auto.apply(&mut route, 1, 3, true);
+        assert_eq!(vec![0, 1, 3, 2, 4], route);
+
+        assert_eq!(Ok(Mutation::Swap), "SWAP".parse());
+        assert_eq!(Ok(Mutation::Insert), Mutation::Insert.to_string().parse());
+        assert!("scramble".parse::<Crossover>().is_err());
+    }
+
+    #[test]
+    fn strategies_inherited_within_bounds() {
+        let mut rng = GeneticRng::seed_from_u64(3);
+
+        let first = Strategy::new(Crossover::Order, 0.04);
+        let second = Strategy::new(Crossover::Pmx, 0.01);
+
+        let kids = (0..200)
+            .map(|_| Strategy::inherit(first, second, &mut rng))
+            .collect::<Vec<_>>();
+
+        for kid in &kids {
+            assert!(kid.mutation_prob >= 0.02 * (-LEARNING_RATE).exp() - 1e-12);
+            assert!(kid.mutation_prob <= 0.02 * LEARNING_RATE.exp() + 1e-12);
+        }
+
+        let switched = kids
+            .iter()
+            .filter(|kid| kid.crossover != first.crossover)
+            .count();
+
+        assert!(switched > 0 && switched < 50);
+
+        let random = Strategy::random(1.0, &mut rng);
+
+        assert!((MIN_MUTATION_PROB..=1.0).contains(&random.mutation_prob));
+    }
+}
@@ -1,12 +1,22 @@
+use std::cmp::Ordering;
 use std::hash::Hash;
 
-use tsp_parser::{KRandom, Tsp, TspHeuristic, TspType};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+use crate::genetic::bandit::Origin;
+use crate::genetic::{GeneticError, GeneticRng, Strategy};
+use crate::problem::PermutationProblem;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PopulationMember {
     route: Vec<usize>,
     route_len: u32,
     chance: f64,
+    #[serde(default)]
+    strategy: Option<Strategy>,
+    #[serde(skip)]
+    origin: Option<Origin>,
 }
 
 impl PartialEq for PopulationMember {
@@ -17,6 +27,18 @@ impl PartialEq for PopulationMember {
 
 impl Eq for PopulationMember {}
 
+impl PartialOrd for PopulationMember {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PopulationMember {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.route_len.cmp(&other.route_len)
+    }
+}
+
 impl Hash for PopulationMember {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.route_len.hash(state);
@@ -24,43 +46,59 @@ impl Hash for PopulationMember {
 }
 
 impl PopulationMember {
-    pub fn new_enhanced(mut route: Vec<usize>, tsp: &Tsp) -> PopulationMember {
-        match tsp.get_tsp_type() {
-            TspType::Symmetric => route = best_neighbourhood_invert(tsp, route),
-            TspType::Asymmetric => route = best_neighbourhood_swap(tsp, route),
-        }
+    pub fn try_new_enhanced<P: PermutationProblem>(
+        route: Vec<usize>,
+        problem: &P,
+    ) -> Result<PopulationMember, GeneticError> {
+        let route_len = problem.evaluate(&route).ok_or(GeneticError::InvalidRoute)?;
+
+        let (route, route_len) = if problem.is_symmetric() {
+            best_neighbourhood_invert(problem, route, route_len)
+        } else {
+            best_neighbourhood_swap(problem, route, route_len)
+        };
+
+        Ok(PopulationMember::from_parts(route, route_len))
+    }
 
-        let route_len = tsp.get_route_len(&route).unwrap();
-        let chance = 1.0 / route_len as f64;
+    pub fn try_new<P: PermutationProblem>(
+        route: Vec<usize>,
+        problem: &P,
+    ) -> Result<PopulationMember, GeneticError> {
+        let route_len = problem.evaluate(&route).ok_or(GeneticError::InvalidRoute)?;
 
-        PopulationMember {
-            route,
-            route_len,
-            chance,
-        }
+        Ok(PopulationMember::from_parts(route, route_len))
     }
 
-    pub fn new(route: Vec<usize>, tsp: &Tsp) -> PopulationMember {
-        let route_len = tsp.get_route_len(&route).unwrap();
-        let chance = 1.0 / route_len as f64;
+    fn from_parts(route: Vec<usize>, route_len: u32) -> PopulationMember {
+        let chance = 1.0 / route_len.max(1) as f64;
 
         PopulationMember {
             route,
             route_len,
             chance,
+            strategy: None,
+            origin: None,
         }
     }
 
-    pub fn gen_random(tsp: &Tsp) -> PopulationMember {
-        let route = KRandom::new(1).get_route(tsp);
-
-        PopulationMember::new(route, tsp)
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = Some(strategy);
+        self
     }
 
-    pub fn gen_random_enhanced(tsp: &Tsp) -> PopulationMember {
-        let route = KRandom::new(1).get_route(tsp);
+    pub fn gen_random<P: PermutationProblem>(
+        problem: &P,
+        rng: &mut GeneticRng,
+    ) -> Result<PopulationMember, GeneticError> {
+        PopulationMember::try_new(random_route(problem, rng), problem)
+    }
 
-        PopulationMember::new_enhanced(route, tsp)
+    pub fn gen_random_enhanced<P: PermutationProblem>(
+        problem: &P,
+        rng: &mut GeneticRng,
+    ) -> Result<PopulationMember, GeneticError> {
+        PopulationMember::try_new_enhanced(random_route(problem, rng), problem)
     }
 
     pub fn get_route(&self) -> &Vec<usize> {
@@ -74,23 +112,51 @@ impl PopulationMember {
     pub fn get_chance(&self) -> f64 {
         self.chance
     }
+
+    pub fn get_strategy(&self) -> Option<Strategy> {
+        self.strategy
+    }
+
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    pub fn get_origin(&self) -> Option<Origin> {
+        self.origin
+    }
+}
+
+fn random_route<P: PermutationProblem>(problem: &P, rng: &mut GeneticRng) -> Vec<usize> {
+    let mut route = (0..problem.dimension()).collect::<Vec<_>>();
+
+    route.shuffle(rng);
+
+    problem.repair(&mut route);
+
+    route
 }
 
-pub fn best_neighbourhood_invert(tsp: &Tsp, route: Vec<usize>) -> Vec<usize> {
-    let dimension = tsp.get_dimension();
+pub fn best_neighbourhood_invert<P: PermutationProblem>(
+    problem: &P,
+    route: Vec<usize>,
+    route_len: u32,
+) -> (Vec<usize>, u32) {
+    let movable = problem.movable_range();
 
     let mut best_route = route;
-    let mut best_route_len = tsp
-        .get_route_len(&best_route)
-        .expect("has to be valid route");
+    let mut best_route_len = route_len;
 
     let mut indexes = (0, 0);
     let mut curr_best_route_len = u32::MAX;
 
     loop {
-        for i in 0..dimension {
-            for j in i + 1..dimension {
-                let route_len = tsp.get_inverted_route_len(&best_route, best_route_len, i, j);
+        for i in movable.clone() {
+            for j in i + 1..movable.end {
+                let route_len = moved_route_len(
+                    best_route_len,
+                    problem.invert_delta(&best_route, best_route_len, i, j),
+                );
 
                 if (route_len < best_route_len)
                     || (curr_best_route_len != u32::MAX && route_len < curr_best_route_len)
@@ -114,24 +180,29 @@ pub fn best_neighbourhood_invert(tsp: &Tsp, route: Vec<usize>) -> Vec<usize> {
         curr_best_route_len = u32::MAX;
     }
 
-    best_route
+    (best_route, best_route_len)
 }
 
-pub fn best_neighbourhood_swap(tsp: &Tsp, route: Vec<usize>) -> Vec<usize> {
-    let dimension = tsp.get_dimension();
+pub fn best_neighbourhood_swap<P: PermutationProblem>(
+    problem: &P,
+    route: Vec<usize>,
+    route_len: u32,
+) -> (Vec<usize>, u32) {
+    let movable = problem.movable_range();
 
     let mut best_route = route;
-    let mut best_route_len = tsp
-        .get_route_len(&best_route)
-        .expect("has to be valid route");
+    let mut best_route_len = route_len;
 
     let mut indexes = (0, 0);
     let mut curr_best_route_len = u32::MAX;
 
     loop {
-        for i in 0..dimension {
-            for j in i + 1..dimension {
-                let route_len = tsp.get_swap_route_len(&best_route, best_route_len, i, j);
+        for i in movable.clone() {
+            for j in i + 1..movable.end {
+                let route_len = moved_route_len(
+                    best_route_len,
+                    problem.swap_delta(&best_route, best_route_len, i, j),
+                );
 
                 if (route_len < best_route_len)
                     || (curr_best_route_len != u32::MAX && route_len < curr_best_route_len)
@@ -155,5 +226,11 @@ pub fn best_neighbourhood_swap(tsp: &Tsp, route: Vec<usize>) -> Vec<usize> {
         curr_best_route_len = u32::MAX;
     }
 
-    best_route
+    (best_route, best_route_len)
+}
+
+fn moved_route_len(route_len: u32, delta: i64) -> u32 {
+    (route_len as i64)
+        .saturating_add(delta)
+        .clamp(0, u32::MAX as i64) as u32
 }
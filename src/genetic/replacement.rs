@@ -0,0 +1,40 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::GeneticError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Replacement {
+    #[default]
+    Generational,
+    Worst,
+    Tournament,
+}
+
+impl FromStr for Replacement {
+    type Err = GeneticError;
+
+    fn from_str(replacement: &str) -> Result<Replacement, GeneticError> {
+        match replacement.to_ascii_lowercase().as_str() {
+            "generational" => Ok(Replacement::Generational),
+            "worst" => Ok(Replacement::Worst),
+            "tournament" => Ok(Replacement::Tournament),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "replacement has to be one of generational, worst or tournament",
+            )),
+        }
+    }
+}
+
+impl Display for Replacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Replacement::Generational => write!(f, "generational"),
+            Replacement::Worst => write!(f, "worst"),
+            Replacement::Tournament => write!(f, "tournament"),
+        }
+    }
+}
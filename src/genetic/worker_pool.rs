@@ -0,0 +1,100 @@
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::Scope;
+
+use crate::genetic::population_member::PopulationMember;
+use crate::genetic::GeneticError;
+
+pub type Job<'scope> =
+    Box<dyn FnOnce() -> Result<Vec<PopulationMember>, GeneticError> + Send + 'scope>;
+
+pub struct WorkerPool<'scope> {
+    job_sender: Sender<(usize, Job<'scope>)>,
+    result_receiver: Receiver<(usize, Result<Vec<PopulationMember>, GeneticError>)>,
+    threads: usize,
+}
+
+impl<'scope> WorkerPool<'scope> {
+    pub fn new<'env>(scope: &'scope Scope<'scope, 'env>, threads: usize) -> WorkerPool<'scope> {
+        let (job_sender, job_receiver) = mpsc::channel::<(usize, Job<'scope>)>();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        for _ in 0..threads {
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+
+            scope.spawn(move || loop {
+                let job = job_receiver.lock().unwrap().recv();
+
+                match job {
+                    Ok((index, job)) => {
+                        let result = panic::catch_unwind(AssertUnwindSafe(job))
+                            .unwrap_or(Err(GeneticError::WorkerPanicked));
+
+                        if result_sender.send((index, result)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+
+        WorkerPool {
+            job_sender,
+            result_receiver,
+            threads,
+        }
+    }
+
+    pub fn get_threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn run(&self, jobs: Vec<Job<'scope>>) -> Result<Vec<Vec<PopulationMember>>, GeneticError> {
+        let jobs_count = jobs.len();
+
+        for job in jobs.into_iter().enumerate() {
+            self.job_sender
+                .send(job)
+                .map_err(|_| GeneticError::WorkerPanicked)?;
+        }
+
+        let mut results = vec![Ok(Vec::new()); jobs_count];
+
+        for _ in 0..jobs_count {
+            let (index, members) = self
+                .result_receiver
+                .recv()
+                .map_err(|_| GeneticError::WorkerPanicked)?;
+
+            results[index] = members;
+        }
+
+        results.into_iter().collect()
+    }
+}
+
+pub fn split_work(items: usize, threads: usize) -> Vec<Range<usize>> {
+    let chunks = threads.min(items);
+
+    let mut ranges = Vec::with_capacity(chunks);
+    let mut start = 0;
+
+    for chunk in 0..chunks {
+        let mut chunk_len = items / chunks;
+
+        if chunk < items % chunks {
+            chunk_len += 1;
+        }
+
+        ranges.push(start..start + chunk_len);
+        start += chunk_len;
+    }
+
+    ranges
+}
@@ -0,0 +1,339 @@
+use crate::genetic::GeneticError;
+use crate::problem::PermutationProblem;
+
+const EARTH_RADIUS: f64 = 6378.388;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Euclidean,
+    RoundedEuclidean,
+    Geographic,
+    Manhattan,
+    Chebyshev,
+}
+
+impl Metric {
+    pub fn distance(&self, from: (f64, f64), to: (f64, f64)) -> u32 {
+        let dx = (from.0 - to.0).abs();
+        let dy = (from.1 - to.1).abs();
+
+        match self {
+            Metric::Euclidean | Metric::RoundedEuclidean => {
+                (dx * dx + dy * dy).sqrt().round() as u32
+            }
+            Metric::Geographic => geographic_distance(from, to),
+            Metric::Manhattan => (dx + dy).round() as u32,
+            Metric::Chebyshev => dx.round().max(dy.round()) as u32,
+        }
+    }
+}
+
+fn geographic_radians(coordinate: f64) -> f64 {
+    let degrees = coordinate.trunc();
+    let minutes = coordinate - degrees;
+
+    std::f64::consts::PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+fn geographic_distance(from: (f64, f64), to: (f64, f64)) -> u32 {
+    let (from_latitude, from_longitude) = (geographic_radians(from.0), geographic_radians(from.1));
+    let (to_latitude, to_longitude) = (geographic_radians(to.0), geographic_radians(to.1));
+
+    let q1 = (from_longitude - to_longitude).cos();
+    let q2 = (from_latitude - to_latitude).cos();
+    let q3 = (from_latitude + to_latitude).cos();
+
+    let angle = (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3))
+        .clamp(-1.0, 1.0)
+        .acos();
+
+    (EARTH_RADIUS * angle + 1.0) as u32
+}
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    dimension: usize,
+    symmetric: bool,
+    distances: Vec<u32>,
+}
+
+impl Instance {
+    pub fn from_coordinates(coordinates: &[(f64, f64)], metric: Metric) -> Instance {
+        let dimension = coordinates.len();
+
+        let mut distances = vec![0; dimension * dimension];
+
+        for i in 0..dimension {
+            for j in i + 1..dimension {
+                let distance = metric.distance(coordinates[i], coordinates[j]);
+
+                distances[i * dimension + j] = distance;
+                distances[j * dimension + i] = distance;
+            }
+        }
+
+        Instance {
+            dimension,
+            symmetric: true,
+            distances,
+        }
+    }
+
+    pub fn from_matrix(matrix: Vec<Vec<u32>>) -> Result<Instance, GeneticError> {
+        let dimension = matrix.len();
+
+        if matrix.iter().any(|row| row.len() != dimension) {
+            return Err(GeneticError::InvalidInstance(
+                "cost matrix has to be square",
+            ));
+        }
+
+        let distances = matrix.into_iter().flatten().collect::<Vec<_>>();
+
+        let mut symmetric = true;
+
+        for i in 0..dimension {
+            for j in i + 1..dimension {
+                if distances[i * dimension + j] != distances[j * dimension + i] {
+                    symmetric = false;
+                }
+            }
+        }
+
+        Ok(Instance {
+            dimension,
+            symmetric,
+            distances,
+        })
+    }
+
+    pub fn from_upper_triangular(rows: Vec<Vec<u32>>) -> Result<Instance, GeneticError> {
+        let dimension = rows.len() + 1;
+
+        let mut distances = vec![0; dimension * dimension];
+
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != dimension - i - 1 {
+                return Err(GeneticError::InvalidInstance(
+                    "row i of upper triangular matrix has to have dimension - i - 1 costs",
+                ));
+            }
+
+            for (offset, distance) in row.into_iter().enumerate() {
+                let j = i + 1 + offset;
+
+                distances[i * dimension + j] = distance;
+                distances[j * dimension + i] = distance;
+            }
+        }
+
+        Ok(Instance {
+            dimension,
+            symmetric: true,
+            distances,
+        })
+    }
+
+    pub fn get_distance(&self, from: usize, to: usize) -> u32 {
+        self.distances[from * self.dimension + to]
+    }
+}
+
+impl PermutationProblem for Instance {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.symmetric
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        if route.len() != self.dimension {
+            return None;
+        }
+
+        let mut visited = vec![false; self.dimension];
+
+        for &city in route {
+            if city >= self.dimension || visited[city] {
+                return None;
+            }
+
+            visited[city] = true;
+        }
+
+        let mut route_len = 0u32;
+
+        for i in 0..route.len() {
+            route_len =
+                route_len.checked_add(self.get_distance(route[i], route[(i + 1) % route.len()]))?;
+        }
+
+        Some(route_len)
+    }
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let n = route.len();
+
+        if i == 0 && j == n - 1 && self.symmetric {
+            return 0;
+        }
+
+        if !self.symmetric {
+            let mut route = route.to_vec();
+
+            route[i..=j].reverse();
+
+            return self.evaluate(&route).unwrap_or(u32::MAX) as i64 - route_len as i64;
+        }
+
+        let before = route[(i + n - 1) % n];
+        let first = route[i];
+        let last = route[j];
+        let after = route[(j + 1) % n];
+
+        self.get_distance(before, last) as i64 + self.get_distance(first, after) as i64
+            - self.get_distance(before, first) as i64
+            - self.get_distance(last, after) as i64
+    }
+
+    fn swap_delta(&self, route: &[usize], _route_len: u32, i: usize, j: usize) -> i64 {
+        let n = route.len();
+
+        let city_at = |position: usize| {
+            if position == i {
+                route[j]
+            } else if position == j {
+                route[i]
+            } else {
+                route[position]
+            }
+        };
+
+        let mut edges = [(i + n - 1) % n, i, (j + n - 1) % n, j];
+        edges.sort_unstable();
+
+        let mut delta = 0;
+
+        for (k, &position) in edges.iter().enumerate() {
+            if k > 0 && edges[k - 1] == position {
+                continue;
+            }
+
+            let next_position = (position + 1) % n;
+
+            delta += self.get_distance(city_at(position), city_at(next_position)) as i64;
+            delta -= self.get_distance(route[position], route[next_position]) as i64;
+        }
+
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::prelude::*;
+
+    use crate::genetic::GeneticRng;
+
+    #[test]
+    fn coordinates_measured_correctly() {
+        let coordinates = vec![(0.0, 0.0), (3.0, 0.0), (3.0, 4.0), (0.0, 4.0)];
+
+        let instance = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+
+        assert_eq!(Some(14), instance.evaluate(&[0, 1, 2, 3]));
+        assert_eq!(Some(18), instance.evaluate(&[0, 2, 1, 3]));
+        assert_eq!(None, instance.evaluate(&[0, 1, 1, 3]));
+
+        assert_eq!(7, Metric::Manhattan.distance((0.0, 0.0), (3.0, 4.0)));
+        assert_eq!(4, Metric::Chebyshev.distance((0.0, 0.0), (3.0, 4.0)));
+        assert_eq!(3, Metric::Euclidean.distance((0.0, 0.0), (1.5, 2.0)));
+        assert_eq!(2, Metric::Euclidean.distance((0.0, 0.0), (1.2, 2.0)));
+    }
+
+    #[test]
+    fn upper_triangular_matches_full_matrix() {
+        let upper =
+            Instance::from_upper_triangular(vec![vec![1, 2, 3], vec![4, 5], vec![6]]).unwrap();
+
+        let full = Instance::from_matrix(vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 4, 5],
+            vec![2, 4, 0, 6],
+            vec![3, 5, 6, 0],
+        ])
+        .unwrap();
+
+        assert!(full.is_symmetric());
+
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(full.get_distance(i, j), upper.get_distance(i, j));
+            }
+        }
+
+        assert!(Instance::from_upper_triangular(vec![vec![1, 2], vec![3, 4]]).is_err());
+    }
+
+    #[test]
+    fn overflowing_route_rejected() {
+        let instance =
+            Instance::from_upper_triangular(vec![vec![u32::MAX / 2, 2], vec![u32::MAX / 2]])
+                .unwrap();
+
+        assert_eq!(None, instance.evaluate(&[0, 1, 2]));
+
+        let instance =
+            Instance::from_upper_triangular(vec![vec![u32::MAX / 2, 1], vec![1]]).unwrap();
+
+        assert_eq!(Some(u32::MAX / 2 + 2), instance.evaluate(&[0, 1, 2]));
+    }
+
+    #[test]
+    fn deltas_match_evaluation() {
+        let mut rng = GeneticRng::seed_from_u64(31);
+
+        let coordinates = (0..15)
+            .map(|_| (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0)))
+            .collect::<Vec<_>>();
+
+        let symmetric = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+
+        let asymmetric = Instance::from_matrix(
+            (0..15)
+                .map(|_| (0..15).map(|_| rng.gen_range(1..100)).collect())
+                .collect(),
+        )
+        .unwrap();
+
+        for instance in [symmetric, asymmetric] {
+            let mut route = (0..15).collect::<Vec<_>>();
+            route.shuffle(&mut rng);
+
+            let route_len = instance.evaluate(&route).unwrap();
+
+            for i in 0..15 {
+                for j in i + 1..15 {
+                    let mut inverted = route.clone();
+                    inverted[i..=j].reverse();
+
+                    let mut swapped = route.clone();
+                    swapped.swap(i, j);
+
+                    assert_eq!(
+                        instance.evaluate(&inverted).unwrap() as i64 - route_len as i64,
+                        instance.invert_delta(&route, route_len, i, j)
+                    );
+                    assert_eq!(
+                        instance.evaluate(&swapped).unwrap() as i64 - route_len as i64,
+                        instance.swap_delta(&route, route_len, i, j)
+                    );
+                }
+            }
+        }
+    }
+}
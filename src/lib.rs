@@ -1,2 +1,18 @@
 pub mod genetic;
 pub mod benchmarking;
+pub mod config;
+pub mod cvrp;
+pub mod generator;
+pub mod instance;
+pub mod multi_objective;
+pub mod orienteering;
+pub mod output;
+pub mod parameters;
+pub mod path;
+pub mod precedence;
+pub mod problem;
+pub mod tsplib;
+pub mod tsptw;
+
+#[cfg(test)]
+mod test_utils;
@@ -1,74 +1,639 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use genetyk::genetic::Genetic;
+use genetyk::benchmarking::{self, Race};
+use genetyk::config::{Experiment, ParameterOverrides};
+use genetyk::cvrp::CvrpInstance;
+use genetyk::generator::{Generator, Layout};
+use genetyk::genetic::{
+    Crossover, Genetic, GeneticError, Mutation, OperatorSelection, Replacement, RunStats,
+};
+use genetyk::multi_objective::{write_front_csv, MultiObjectiveInstance};
+use genetyk::orienteering::{OrienteeringInstance, PrizeObjective};
+use genetyk::output::{write_solution, OutputFormat, SolutionReport};
+use genetyk::path::{PathInstance, PathMode};
+use genetyk::precedence::{read_constraints, Precedence};
+use genetyk::problem::PermutationProblem;
+use genetyk::tsplib::read_tours;
+use genetyk::tsptw::{TsptwInstance, TsptwWeights};
 use tsp_parser::*;
 
 #[derive(Parser, Debug)]
-struct Args {
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    Solve(SolveArgs),
+    Bench(BenchArgs),
+    Tune(TuneArgs),
+    Generate(GenerateArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct SolveArgs {
     #[clap(short, long)]
     path: String,
+    #[clap(short, long, help = "Known optimum, used to report the gap")]
+    opt: Option<u32>,
+    #[clap(long)]
+    cvrp: bool,
+    #[clap(long)]
+    tsptw: bool,
+    #[clap(long)]
+    violation_weight: Option<u32>,
+    #[clap(long)]
+    lexicographic: bool,
+    #[clap(long)]
+    open: bool,
+    #[clap(long, help = "First node of the path, numbered from 1 as in TSPLIB")]
+    start: Option<usize>,
+    #[clap(long, help = "Last node of the path, numbered from 1 as in TSPLIB")]
+    end: Option<usize>,
+    #[clap(long)]
+    precedence: Option<String>,
+    #[clap(long)]
+    sop: bool,
+    #[clap(long)]
+    budget: Option<u32>,
+    #[clap(long)]
+    penalty: Option<u32>,
+    #[clap(long)]
+    pareto: Vec<String>,
+    #[clap(long)]
+    front_output: Option<String>,
+    #[clap(long)]
+    seed: Option<u64>,
+    #[clap(long)]
+    checkpoint: Option<String>,
+    #[clap(long, default_value = "10")]
+    checkpoint_every: usize,
+    #[clap(long)]
+    resume: Option<String>,
+    #[clap(long)]
+    initial_tour: Vec<String>,
+    #[clap(long, default_value = "plain")]
+    output_format: OutputFormat,
+    #[clap(long)]
+    output: Option<String>,
+    #[clap(long)]
+    config: Option<String>,
+    #[clap(flatten)]
+    genetic: GeneticArgs,
+}
+
+#[derive(clap::Args, Debug)]
+struct GeneticArgs {
+    #[clap(long)]
+    iterations: Option<usize>,
+    #[clap(long)]
+    population_size: Option<usize>,
+    #[clap(long)]
+    elites: Option<usize>,
+    #[clap(long)]
+    pairs: Option<usize>,
+    #[clap(long)]
+    crossing_prob: Option<f64>,
+    #[clap(long)]
+    mutation_prob: Option<f64>,
+    #[clap(long)]
+    stagnation: Option<usize>,
+    #[clap(long)]
+    max_mutation_multiply: Option<usize>,
+    #[clap(long)]
+    threads: Option<usize>,
+    #[clap(long)]
+    memetic_fraction: Option<f64>,
+    #[clap(long)]
+    crossover: Option<Crossover>,
+    #[clap(long)]
+    mutation: Option<Mutation>,
+    #[clap(long)]
+    self_adaptive: bool,
+    #[clap(long)]
+    operator_selection: Option<OperatorSelection>,
+    #[clap(long)]
+    replacement: Option<Replacement>,
+}
+
+impl GeneticArgs {
+    fn overrides(&self) -> ParameterOverrides {
+        ParameterOverrides {
+            iterations: self.iterations,
+            population_size: self.population_size,
+            elites_count: self.elites,
+            pair_count: self.pairs,
+            crossing_prob: self.crossing_prob,
+            mutation_prob: self.mutation_prob,
+            stagnation_iter: self.stagnation,
+            max_mutation_multiply: self.max_mutation_multiply,
+            threads: self.threads,
+            memetic_fraction: self.memetic_fraction,
+            crossover: self.crossover,
+            mutation: self.mutation,
+            self_adaptive: self.self_adaptive.then_some(true),
+            operator_selection: self.operator_selection,
+            replacement: self.replacement,
+            ..ParameterOverrides::default()
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct BenchArgs {
+    #[clap(
+        possible_values = &["runtime", "threads", "worker-pool"],
+        required_unless_present = "experiment"
+    )]
+    benchmark: Option<String>,
+    #[clap(long, conflicts_with = "benchmark")]
+    experiment: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct TuneArgs {
+    experiment: String,
+    #[clap(long, default_value = "tuned.toml")]
+    output: String,
+    #[clap(long, default_value = "2")]
+    elimination_factor: usize,
+}
+
+#[derive(clap::Args, Debug)]
+struct GenerateArgs {
+    #[clap(long, default_value = "uniform")]
+    layout: Layout,
     #[clap(short, long)]
-    opt: u32,
+    dimension: usize,
+    #[clap(long, default_value = "1")]
+    seed: u64,
+    #[clap(long, default_value = "1000")]
+    extent: u32,
+    #[clap(long)]
+    clusters: Option<usize>,
+    #[clap(long)]
+    name: Option<String>,
+    #[clap(long)]
+    output: Option<String>,
 }
 
 fn main() {
-    let args = Args::parse();
+    match Cli::parse().command {
+        Command::Solve(args) => solve(&args),
+        Command::Bench(args) => bench(&args),
+        Command::Tune(args) => tune(&args),
+        Command::Generate(args) => generate(&args),
+    }
+}
 
-    run_from_file(&args.path, args.opt);
+fn bench(args: &BenchArgs) {
+    if let Some(path) = &args.experiment {
+        return run_experiment(Experiment::from_file(path));
+    }
+
+    match args.benchmark.as_deref() {
+        Some("runtime") => benchmarking::bench_runtime(),
+        Some("threads") => benchmarking::bench_threads(),
+        _ => benchmarking::bench_worker_pool(),
+    }
 }
 
-fn run_from_file(file_name: &str, opt: u32) {
-    let tsp = TspParser::from_file(file_name).unwrap();
+fn tune(args: &TuneArgs) {
+    let tuned = Experiment::from_file(&args.experiment).and_then(|experiment| {
+        let outcome = Race::from_experiment(&experiment)
+            .with_elimination_factor(args.elimination_factor)
+            .run()?;
 
-    let dimension = tsp.get_dimension();
+        outcome.best.1.to_file(&args.output)?;
 
-    let mut iterations = if file_name.contains("atsp") {
-        10 * dimension
-    } else {
-        dimension
+        Ok(outcome.best.0)
+    });
+
+    match tuned {
+        Ok(label) => println!("Best configuration {} written to {}", label, args.output),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn generate(args: &GenerateArgs) {
+    let mut generator =
+        Generator::new(args.layout, args.dimension, args.seed).with_extent(args.extent);
+
+    if let Some(clusters) = args.clusters {
+        generator = generator.with_clusters(clusters);
+    }
+
+    let name = args.name.clone().unwrap_or_else(|| generator.get_name());
+    let generated = generator.generate();
+
+    match &args.output {
+        Some(path) => {
+            if let Err(err) = generated.write_tsplib(path, &name) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        None => print!("{}", generated.to_tsplib(&name)),
+    }
+}
+
+fn run_experiment(experiment: Result<Experiment, GeneticError>) {
+    if let Err(err) = experiment.and_then(|experiment| benchmarking::run_experiment(&experiment)) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn solve(args: &SolveArgs) {
+    let start = args.start.map(|node| node_index(node, "--start"));
+    let end = args.end.map(|node| node_index(node, "--end"));
+
+    let path_mode = match (args.open, start, end) {
+        (false, None, None) => None,
+        (true, None, None) => Some(PathMode::Open),
+        (_, Some(start), None) => Some(PathMode::FixedStart(start)),
+        (_, Some(start), Some(end)) => Some(PathMode::FixedEnds(start, end)),
+        (_, None, Some(_)) => {
+            eprintln!("--end requires --start");
+            std::process::exit(1);
+        }
+    };
+
+    let prize_objective = match (args.budget, args.penalty) {
+        (None, None) => None,
+        (Some(budget), None) => Some(PrizeObjective::Budget(budget)),
+        (None, Some(weight)) => Some(PrizeObjective::Penalty(weight)),
+        (Some(_), Some(_)) => {
+            eprintln!("--budget and --penalty cannot be used together");
+            std::process::exit(1);
+        }
     };
 
-    if iterations < 100 {
-        iterations = 100;
+    let modes = [
+        ("--cvrp", args.cvrp),
+        ("--tsptw", args.tsptw),
+        ("--pareto", !args.pareto.is_empty()),
+        ("--budget and --penalty", prize_objective.is_some()),
+        ("--sop", args.sop),
+    ];
+
+    let mut chosen_modes = modes.iter().filter(|(_, chosen)| *chosen);
+
+    if let (Some((first, _)), Some((second, _))) = (chosen_modes.next(), chosen_modes.next()) {
+        eprintln!("{} cannot be used together with {}", first, second);
+        std::process::exit(1);
     }
 
-    let population_size = 10 * dimension;
+    let path_flags = [
+        ("--open", args.open),
+        ("--start", start.is_some()),
+        ("--end", end.is_some()),
+        ("--precedence", args.precedence.is_some()),
+    ];
 
-    let elites = if population_size / 2 == 0 {
-        1
+    let checkpoint_flags = [
+        ("--checkpoint", args.checkpoint.is_some()),
+        ("--resume", args.resume.is_some()),
+    ];
+
+    if args.front_output.is_some() && args.pareto.is_empty() {
+        eprintln!("--front-output requires --pareto");
+        std::process::exit(1);
+    }
+
+    if (args.violation_weight.is_some() || args.lexicographic) && !args.tsptw {
+        eprintln!("--violation-weight and --lexicographic require --tsptw");
+        std::process::exit(1);
+    }
+
+    if args.cvrp {
+        reject_output_flags(args, "--cvrp");
+        reject_flags(&path_flags, "--cvrp");
+        reject_flags(&checkpoint_flags, "--cvrp");
+        run_cvrp_from_file(args);
+    } else if args.tsptw {
+        reject_output_flags(args, "--tsptw");
+        reject_flags(&path_flags, "--tsptw");
+        reject_flags(&checkpoint_flags, "--tsptw");
+        run_tsptw_from_file(args);
+    } else if !args.pareto.is_empty() {
+        reject_output_flags(args, "--pareto, use --front-output instead");
+        reject_flags(&path_flags, "--pareto");
+        reject_flags(&checkpoint_flags, "--pareto");
+
+        let overrides = parameter_overrides(args);
+
+        reject_flags(
+            &[
+                ("elites_count", overrides.elites_count.is_some()),
+                ("stagnation_iter", overrides.stagnation_iter.is_some()),
+                ("max_mutation_multiply", overrides.max_mutation_multiply.is_some()),
+                ("memetic_fraction", overrides.memetic_fraction.is_some()),
+            ],
+            "--pareto, NSGA-II keeps no elites and runs no local search",
+        );
+
+        run_pareto_from_files(args);
+    } else if let Some(objective) = prize_objective {
+        reject_output_flags(args, "--budget and --penalty");
+        reject_flags(&path_flags, "--budget and --penalty");
+        reject_flags(&checkpoint_flags, "--budget and --penalty");
+        run_orienteering_from_file(args, objective);
+    } else if args.sop {
+        reject_flags(&path_flags, "--sop, the SOP file fixes both endpoints");
+        run_problem(args, Precedence::from_sop_file(&args.path));
+    } else if let Some(constraints_file) = &args.precedence {
+        let constraints = read_constraints(constraints_file);
+
+        match path_mode {
+            Some(mode) => run_problem(
+                args,
+                PathInstance::from_file(&args.path, mode)
+                    .and_then(|path| Precedence::new(path, constraints?)),
+            ),
+            None => {
+                eprintln!(
+                    "--precedence requires --open or --start, a closed tour has no first city"
+                );
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(mode) = path_mode {
+        run_problem(args, PathInstance::from_file(&args.path, mode));
     } else {
-        population_size / 2
+        run_from_file(args);
+    }
+}
+
+fn reject_output_flags(args: &SolveArgs, mode: &str) {
+    if args.output.is_some() || args.output_format != OutputFormat::Plain {
+        eprintln!(
+            "--output and --output-format are not supported with {}",
+            mode
+        );
+        std::process::exit(1);
+    }
+}
+
+fn reject_flags(flags: &[(&str, bool)], mode: &str) {
+    let used = flags
+        .iter()
+        .filter(|(_, used)| *used)
+        .map(|(flag, _)| *flag)
+        .collect::<Vec<_>>();
+
+    if !used.is_empty() {
+        eprintln!("{} not supported with {}", used.join(" and "), mode);
+        std::process::exit(1);
+    }
+}
+
+fn node_index(node: usize, flag: &str) -> usize {
+    if node == 0 {
+        eprintln!("{} takes a 1-based node number", flag);
+        std::process::exit(1);
+    }
+
+    node - 1
+}
+
+fn run_from_file(args: &SolveArgs) {
+    let tsp = TspParser::from_file(&args.path)
+        .map_err(|err| GeneticError::InvalidFile(format!("{}: {:?}", args.path, err)));
+
+    run_problem(args, tsp);
+}
+
+fn run_problem<P: PermutationProblem>(args: &SolveArgs, problem: Result<P, GeneticError>) {
+    let problem = match problem {
+        Ok(problem) => problem,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
     };
 
-    let pairs = population_size / 2;
-    let kid_prob = 0.9;
-    let mutation_prob = 0.02;
-    let stagnation_iter = iterations / 5;
-    let mutation_steps = 4;
-    let memetic_fraction = 0.1;
-    let threads = 5;
-
-    let genetic = Genetic::new(
-        opt,
-        iterations,
-        population_size,
-        elites,
-        pairs,
-        kid_prob,
-        mutation_prob,
-        stagnation_iter,
-        mutation_steps,
-        threads,
-        memetic_fraction,
-    );
-
-    let route = genetic.get_route(&tsp);
+    let genetic = genetic_for_problem(args, &problem);
+
+    let solved = match &args.resume {
+        Some(checkpoint) => genetic.try_resume_with_stats(&problem, checkpoint),
+        None => genetic.try_solve_with_stats(&problem),
+    };
+
+    write_route(args, &problem, solved);
+}
+
+fn write_route<P: PermutationProblem>(
+    args: &SolveArgs,
+    problem: &P,
+    solved: Result<(Vec<usize>, RunStats), GeneticError>,
+) {
+    let (route, stats) = match solved {
+        Ok(solved) => solved,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let name = std::path::Path::new(&args.path)
+        .file_stem()
+        .map_or(String::new(), |stem| stem.to_string_lossy().to_string());
+
+    let length = problem.evaluate(&route).unwrap_or(u32::MAX);
+
+    let report = SolutionReport::new(&name, route, length, args.opt.unwrap_or(0), stats);
+
+    let written = match &args.output {
+        Some(path) => std::fs::File::create(path)
+            .and_then(|mut file| write_solution(&mut file, &report, args.output_format)),
+        None => {
+            if args.output_format == OutputFormat::Plain {
+                println!();
+            }
+
+            write_solution(&mut std::io::stdout(), &report, args.output_format)
+        }
+    };
+
+    if let Err(err) = written {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run_orienteering_from_file(args: &SolveArgs, objective: PrizeObjective) {
+    let solution =
+        OrienteeringInstance::from_file(&args.path, objective).and_then(|orienteering| {
+            let genetic = genetic_for_problem(args, &orienteering);
+
+            orienteering.solve(&genetic)
+        });
+
+    let solution = match solution {
+        Ok(solution) => solution,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
 
     println!();
 
-    for city in &route {
+    for city in &solution.route {
         print!("{} ", city);
     }
 
     println!();
+    println!("Prize {}", solution.prize);
+    println!("Length {}", solution.length);
+}
+
+fn run_pareto_from_files(args: &SolveArgs) {
+    let mut paths = vec![args.path.clone()];
+    paths.extend_from_slice(&args.pareto);
+
+    let front = MultiObjectiveInstance::from_files(&paths).and_then(|multi_objective| {
+        let genetic = genetic_for_problem(args, &multi_objective);
+
+        multi_objective.solve(&genetic)
+    });
+
+    let front = match front {
+        Ok(front) => front,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let written = match &args.front_output {
+        Some(path) => {
+            std::fs::File::create(path).and_then(|mut file| write_front_csv(&mut file, &front))
+        }
+        None => write_front_csv(&mut std::io::stdout(), &front),
+    };
+
+    if let Err(err) = written {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run_cvrp_from_file(args: &SolveArgs) {
+    let solution = CvrpInstance::from_file(&args.path).and_then(|cvrp| {
+        let genetic = genetic_for_problem(args, &cvrp);
+
+        cvrp.solve(&genetic)
+    });
+
+    let solution = match solution {
+        Ok(solution) => solution,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    println!();
+
+    for (i, route) in solution.routes.iter().enumerate() {
+        print!("Route #{}:", i + 1);
+
+        for node in route {
+            print!(" {}", node);
+        }
+
+        println!();
+    }
+
+    println!("Cost {}", solution.cost);
+}
+
+fn run_tsptw_from_file(args: &SolveArgs) {
+    let weights = TsptwWeights {
+        violation_weight: args
+            .violation_weight
+            .unwrap_or(TsptwWeights::default().violation_weight),
+        lexicographic: args.lexicographic,
+        ..TsptwWeights::default()
+    };
+
+    let schedule = TsptwInstance::from_file(&args.path, weights).and_then(|tsptw| {
+        let genetic = genetic_for_problem(args, &tsptw);
+
+        tsptw.solve(&genetic)
+    });
+
+    let schedule = match schedule {
+        Ok(schedule) => schedule,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    println!();
+
+    for (city, arrival) in schedule.route.iter().zip(&schedule.arrivals) {
+        println!("{} at {}", city, arrival);
+    }
+
+    if let Some(arrival) = schedule.arrivals.last() {
+        println!("{} at {}", schedule.route[0], arrival);
+    }
+
+    println!("Travel time {}", schedule.travel_time);
+    println!("Violation {}", schedule.violation);
+}
+
+fn parameter_overrides(args: &SolveArgs) -> ParameterOverrides {
+    let file_overrides = match &args.config {
+        Some(path) => match ParameterOverrides::from_file(path) {
+            Ok(overrides) => overrides,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        },
+        None => ParameterOverrides::default(),
+    };
+
+    file_overrides.merged(&args.genetic.overrides())
+}
+
+fn genetic_for_problem<P: PermutationProblem>(args: &SolveArgs, problem: &P) -> Genetic {
+    let parameters = parameter_overrides(args).resolve(problem);
+
+    let mut genetic = parameters.to_genetic(args.opt.unwrap_or(0));
+
+    if let Some(seed) = args.seed {
+        genetic = genetic.with_seed(seed);
+    }
+
+    if let Some(checkpoint) = &args.checkpoint {
+        genetic = genetic.with_checkpoint(checkpoint, args.checkpoint_every);
+    }
+
+    if !args.initial_tour.is_empty() {
+        let mut routes = vec![];
+
+        for path in &args.initial_tour {
+            match read_tours(path) {
+                Ok(tours) => routes.extend(tours),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        genetic = genetic.with_initial_routes(routes);
+    }
+
+    genetic
 }
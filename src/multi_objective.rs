@@ -0,0 +1,206 @@
+use std::io::Write;
+
+use crate::genetic::{Genetic, GeneticError, ParetoMember};
+use crate::instance::Instance;
+use crate::problem::{MultiObjectiveProblem, PermutationProblem};
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone)]
+pub struct MultiObjectiveInstance {
+    objectives: Vec<Instance>,
+}
+
+impl MultiObjectiveInstance {
+    pub fn new(objectives: Vec<Instance>) -> Result<MultiObjectiveInstance, GeneticError> {
+        if objectives.len() < 2 {
+            return Err(GeneticError::InvalidInstance(
+                "multi-objective instance needs at least two cost matrices",
+            ));
+        }
+
+        let dimension = objectives[0].dimension();
+
+        if objectives
+            .iter()
+            .any(|objective| objective.dimension() != dimension)
+        {
+            return Err(GeneticError::InvalidInstance(
+                "cost matrices have to have the same dimension",
+            ));
+        }
+
+        Ok(MultiObjectiveInstance { objectives })
+    }
+
+    pub fn from_files(paths: &[String]) -> Result<MultiObjectiveInstance, GeneticError> {
+        let mut objectives = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            objectives.push(TsplibFile::from_file(path)?.to_instance()?);
+        }
+
+        MultiObjectiveInstance::new(objectives)
+    }
+
+    pub fn get_objective(&self, objective: usize) -> &Instance {
+        &self.objectives[objective]
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<Vec<ParetoMember>, GeneticError> {
+        genetic.try_solve_pareto(self)
+    }
+}
+
+impl PermutationProblem for MultiObjectiveInstance {
+    fn dimension(&self) -> usize {
+        self.objectives[0].dimension()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.objectives
+            .iter()
+            .all(|objective| objective.is_symmetric())
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        let mut route_len = 0u32;
+
+        for objective in &self.objectives {
+            route_len = route_len.saturating_add(objective.evaluate(route)?);
+        }
+
+        Some(route_len)
+    }
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        self.objectives
+            .iter()
+            .map(|objective| objective.invert_delta(route, route_len, i, j))
+            .sum()
+    }
+
+    fn swap_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        self.objectives
+            .iter()
+            .map(|objective| objective.swap_delta(route, route_len, i, j))
+            .sum()
+    }
+}
+
+impl MultiObjectiveProblem for MultiObjectiveInstance {
+    fn objectives_count(&self) -> usize {
+        self.objectives.len()
+    }
+
+    fn evaluate_objectives(&self, route: &[usize]) -> Option<Vec<u32>> {
+        self.objectives
+            .iter()
+            .map(|objective| objective.evaluate(route))
+            .collect()
+    }
+}
+
+pub fn write_front_csv<W: Write>(writer: &mut W, front: &[ParetoMember]) -> std::io::Result<()> {
+    let objectives_count = front.first().map_or(0, |member| member.objectives.len());
+
+    for objective in 0..objectives_count {
+        write!(writer, "objective_{},", objective + 1)?;
+    }
+
+    writeln!(writer, "route")?;
+
+    for member in front {
+        for objective in &member.objectives {
+            write!(writer, "{},", objective)?;
+        }
+
+        let route = member
+            .route
+            .iter()
+            .map(|city| city.to_string())
+            .collect::<Vec<_>>();
+
+        writeln!(writer, "{}", route.join(" "))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::genetic::{crowding_distances, non_dominated_sort};
+    use crate::instance::Metric;
+
+    fn member(objectives: Vec<u32>) -> ParetoMember {
+        ParetoMember {
+            route: vec![],
+            objectives,
+        }
+    }
+
+    #[test]
+    fn fronts_sorted_and_crowded_correctly() {
+        let members = vec![
+            member(vec![1, 5]),
+            member(vec![2, 2]),
+            member(vec![3, 3]),
+            member(vec![5, 1]),
+            member(vec![4, 4]),
+        ];
+
+        let fronts = non_dominated_sort(&members);
+
+        assert_eq!(vec![vec![0, 1, 3], vec![2], vec![4]], fronts);
+
+        let distances = crowding_distances(&members, &fronts[0]);
+
+        assert!(distances[0].is_infinite());
+        assert_eq!(2.0, distances[1]);
+        assert!(distances[2].is_infinite());
+    }
+
+    #[test]
+    fn pareto_front_is_non_dominated() {
+        let coordinates = (0..10)
+            .map(|i| ((i * 37 % 100) as f64, (i * 61 % 100) as f64))
+            .collect::<Vec<_>>();
+
+        let distance = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+        let toll = Instance::from_coordinates(&coordinates, Metric::Manhattan);
+
+        let multi_objective = MultiObjectiveInstance::new(vec![distance, toll]).unwrap();
+
+        assert!(
+            MultiObjectiveInstance::new(vec![multi_objective.get_objective(0).clone()]).is_err()
+        );
+
+        let genetic = Genetic::new(0, 30, 20, 5, 10, 0.9, 0.05, 10, 4, 2, 0.0);
+
+        let front = multi_objective.solve(&genetic).unwrap();
+
+        assert!(!front.is_empty());
+        assert!(matches!(
+            multi_objective.solve(&genetic.with_self_adaptation(true)),
+            Err(GeneticError::InvalidConfiguration(_))
+        ));
+
+        for first in &front {
+            assert_eq!(
+                Some(first.objectives.clone()),
+                multi_objective.evaluate_objectives(&first.route)
+            );
+
+            assert!(front.iter().all(|second| !second.dominates(first)));
+        }
+
+        let mut csv = vec![];
+        write_front_csv(&mut csv, &front).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+
+        assert!(csv.starts_with("objective_1,objective_2,route\n"));
+        assert_eq!(front.len() + 1, csv.lines().count());
+    }
+}
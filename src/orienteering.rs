@@ -0,0 +1,297 @@
+use std::ops::Range;
+
+use rand::prelude::*;
+
+use crate::genetic::{Genetic, GeneticError, GeneticRng};
+use crate::instance::Instance;
+use crate::problem::{insert, PermutationProblem};
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrizeObjective {
+    Budget(u32),
+    Penalty(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrienteeringSolution {
+    pub route: Vec<usize>,
+    pub prize: u64,
+    pub length: u32,
+    pub fitness: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrienteeringInstance {
+    instance: Instance,
+    prizes: Vec<u32>,
+    depot: usize,
+    objective: PrizeObjective,
+    total_prize: u64,
+}
+
+impl OrienteeringInstance {
+    pub fn new(
+        instance: Instance,
+        prizes: Vec<u32>,
+        depot: usize,
+        objective: PrizeObjective,
+    ) -> Result<OrienteeringInstance, GeneticError> {
+        let dimension = instance.dimension();
+
+        if depot >= dimension {
+            return Err(GeneticError::InvalidInstance(
+                "depot is not a node of the instance",
+            ));
+        }
+
+        if prizes.len() != dimension {
+            return Err(GeneticError::InvalidInstance(
+                "every node of the instance needs a prize",
+            ));
+        }
+
+        let total_prize = prizes.iter().map(|&prize| prize as u64).sum();
+
+        Ok(OrienteeringInstance {
+            instance,
+            prizes,
+            depot,
+            objective,
+            total_prize,
+        })
+    }
+
+    pub fn from_file(
+        path: &str,
+        objective: PrizeObjective,
+    ) -> Result<OrienteeringInstance, GeneticError> {
+        let file = TsplibFile::from_file(path)?;
+
+        OrienteeringInstance::from_tsplib(&file, objective)
+    }
+
+    pub fn from_tsplib(
+        file: &TsplibFile,
+        objective: PrizeObjective,
+    ) -> Result<OrienteeringInstance, GeneticError> {
+        let instance = file.to_instance()?;
+
+        let prizes = file.get_node_values("PRIZE_SECTION", 0)?;
+
+        let depot = file.get_depot()?;
+
+        OrienteeringInstance::new(instance, prizes, depot, objective)
+    }
+
+    pub fn get_objective(&self) -> PrizeObjective {
+        self.objective
+    }
+
+    pub fn get_prize(&self, node: usize) -> u32 {
+        self.prizes[node]
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<OrienteeringSolution, GeneticError> {
+        let route = genetic.try_solve(self)?;
+
+        self.decode(&route).ok_or(GeneticError::InvalidRoute)
+    }
+
+    pub fn decode(&self, route: &[usize]) -> Option<OrienteeringSolution> {
+        let marker = self.instance.dimension();
+
+        if route.len() != marker + 1 || route.first() != Some(&self.depot) {
+            return None;
+        }
+
+        let mut visited = vec![false; marker + 1];
+
+        for &city in route {
+            if city > marker || visited[city] {
+                return None;
+            }
+
+            visited[city] = true;
+        }
+
+        let marker_position = route.iter().position(|&city| city == marker)?;
+        let route = route[..marker_position].to_vec();
+
+        let prize = route.iter().map(|&city| self.prizes[city] as u64).sum();
+
+        let length = (0..route.len()).try_fold(0u32, |length, i| {
+            length.checked_add(
+                self.instance
+                    .get_distance(route[i], route[(i + 1) % route.len()]),
+            )
+        })?;
+
+        let fitness = self.fitness(prize, length);
+
+        Some(OrienteeringSolution {
+            route,
+            prize,
+            length,
+            fitness,
+        })
+    }
+
+    fn fitness(&self, prize: u64, length: u32) -> u32 {
+        let missed_prize = self.total_prize - prize;
+        let length = length as u64;
+
+        let fitness = match self.objective {
+            PrizeObjective::Budget(budget) => {
+                let budget = budget as u64;
+
+                if length <= budget {
+                    missed_prize * (budget + 1) + length
+                } else {
+                    (self.total_prize + 1) * (budget + 1) + length - budget
+                }
+            }
+            PrizeObjective::Penalty(weight) => length + weight as u64 * missed_prize,
+        };
+
+        fitness.min(u32::MAX as u64) as u32
+    }
+}
+
+impl PermutationProblem for OrienteeringInstance {
+    fn dimension(&self) -> usize {
+        self.instance.dimension() + 1
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.instance.is_symmetric()
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        self.decode(route).map(|solution| solution.fitness)
+    }
+
+    fn movable_range(&self) -> Range<usize> {
+        1..self.instance.dimension() + 1
+    }
+
+    fn repair(&self, route: &mut [usize]) {
+        if let Some(position) = route.iter().position(|&city| city == self.depot) {
+            route.swap(position, 0);
+        }
+    }
+
+    fn mutate(&self, route: &mut [usize], mutation_prob: f64, rng: &mut GeneticRng) {
+        let marker = self.instance.dimension();
+
+        let marker_position = match route.iter().position(|&city| city == marker) {
+            Some(position) => position,
+            None => return,
+        };
+
+        if mutation_prob <= rng.gen() {
+            return;
+        }
+
+        let can_drop = marker_position > 1;
+        let can_add = marker_position < marker;
+
+        if can_drop && (!can_add || rng.gen_bool(0.5)) {
+            let dropped = rng.gen_range(1..marker_position);
+
+            insert(route, dropped, marker);
+        } else if can_add {
+            let added = rng.gen_range(marker_position + 1..=marker);
+            let target = rng.gen_range(1..=marker_position);
+
+            insert(route, added, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::instance::Metric;
+    use crate::test_utils::{line_instance, small_genetic};
+
+    fn square() -> Instance {
+        let coordinates = vec![(0.0, 0.0), (3.0, 0.0), (3.0, 4.0), (0.0, 4.0)];
+
+        Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean)
+    }
+
+    #[test]
+    fn subset_decoded_correctly() {
+        let prizes = vec![0, 10, 30, 20];
+
+        let budget =
+            OrienteeringInstance::new(square(), prizes.clone(), 0, PrizeObjective::Budget(12))
+                .unwrap();
+
+        let solution = budget.decode(&[0, 3, 2, 4, 1]).unwrap();
+
+        assert_eq!(vec![0, 3, 2], solution.route);
+        assert_eq!(50, solution.prize);
+        assert_eq!(12, solution.length);
+        assert_eq!(10 * 13 + 12, solution.fitness);
+
+        assert!(
+            budget.evaluate(&[0, 1, 2, 3, 4]).unwrap() > budget.evaluate(&[0, 4, 1, 2, 3]).unwrap()
+        );
+        assert_eq!(None, budget.evaluate(&[1, 0, 2, 3, 4]));
+
+        let penalty =
+            OrienteeringInstance::new(square(), prizes, 0, PrizeObjective::Penalty(2)).unwrap();
+
+        assert_eq!(Some(12 + 2 * 10), penalty.evaluate(&[0, 3, 2, 4, 1]));
+        assert_eq!(Some(2 * 60), penalty.evaluate(&[0, 4, 3, 2, 1]));
+    }
+
+    #[test]
+    fn overflowing_length_rejected() {
+        let far = u32::MAX / 2 + 1;
+        let instance = Instance::from_matrix(vec![vec![0, far], vec![far, 0]]).unwrap();
+
+        let orienteering =
+            OrienteeringInstance::new(instance, vec![0, 5], 0, PrizeObjective::Penalty(1)).unwrap();
+
+        assert_eq!(None, orienteering.decode(&[0, 1, 2]));
+        assert_eq!(Some(5), orienteering.evaluate(&[0, 2, 1]));
+    }
+
+    #[test]
+    fn add_drop_mutation_keeps_route_valid() {
+        let orienteering =
+            OrienteeringInstance::new(square(), vec![0, 1, 2, 3], 0, PrizeObjective::Penalty(1))
+                .unwrap();
+
+        let mut route = vec![0, 1, 4, 2, 3];
+
+        let mut rng = GeneticRng::seed_from_u64(7);
+
+        for _ in 0..100 {
+            orienteering.mutate(&mut route, 1.0, &mut rng);
+
+            assert!(orienteering.decode(&route).is_some());
+        }
+    }
+
+    #[test]
+    fn budget_respected_by_solution() {
+        let instance = line_instance(10);
+        let prizes = (0..10).map(|i| 10 + i as u32).collect();
+
+        let orienteering =
+            OrienteeringInstance::new(instance, prizes, 0, PrizeObjective::Budget(100)).unwrap();
+
+        let genetic = small_genetic();
+
+        let solution = orienteering.solve(&genetic).unwrap();
+
+        assert!(solution.length <= 100);
+        assert_eq!(0, solution.route[0]);
+        assert!(solution.prize > 10);
+    }
+}
@@ -0,0 +1,181 @@
+use std::io::Write;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::genetic::{prd, GeneticError, RunStats};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Plain,
+    Tour,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = GeneticError;
+
+    fn from_str(format: &str) -> Result<OutputFormat, GeneticError> {
+        match format.to_ascii_lowercase().as_str() {
+            "plain" => Ok(OutputFormat::Plain),
+            "tour" => Ok(OutputFormat::Tour),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(GeneticError::InvalidConfiguration(
+                "output format has to be one of plain, tour, json or csv",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SolutionReport {
+    pub name: String,
+    pub route: Vec<usize>,
+    pub length: u32,
+    pub gap: Option<f64>,
+    pub stats: RunStats,
+}
+
+impl SolutionReport {
+    pub fn new(
+        name: &str,
+        route: Vec<usize>,
+        length: u32,
+        opt: u32,
+        stats: RunStats,
+    ) -> SolutionReport {
+        let gap = if opt > 0 {
+            Some(prd(length, opt))
+        } else {
+            None
+        };
+
+        SolutionReport {
+            name: name.to_string(),
+            route,
+            length,
+            gap,
+            stats,
+        }
+    }
+}
+
+pub fn write_solution<W: Write>(
+    writer: &mut W,
+    report: &SolutionReport,
+    format: OutputFormat,
+) -> std::io::Result<()> {
+    match format {
+        OutputFormat::Plain => {
+            for city in &report.route {
+                write!(writer, "{} ", city)?;
+            }
+
+            writeln!(writer)
+        }
+        OutputFormat::Tour => write_tour(writer, report),
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, report)?;
+
+            writeln!(writer)
+        }
+        OutputFormat::Csv => {
+            writeln!(writer, "name,length,gap,generations,elapsed_secs,route")?;
+
+            let gap = report
+                .gap
+                .map_or(String::new(), |gap| format!("{:.4}", gap));
+
+            writeln!(
+                writer,
+                "{},{},{},{},{:.3},{}",
+                report.name,
+                report.length,
+                gap,
+                report.stats.generations,
+                report.stats.elapsed_secs,
+                join_route(&report.route)
+            )
+        }
+    }
+}
+
+fn write_tour<W: Write>(writer: &mut W, report: &SolutionReport) -> std::io::Result<()> {
+    writeln!(writer, "NAME : {}.tour", report.name)?;
+    writeln!(writer, "COMMENT : Length = {}", report.length)?;
+    writeln!(writer, "TYPE : TOUR")?;
+    writeln!(writer, "DIMENSION : {}", report.route.len())?;
+    writeln!(writer, "TOUR_SECTION")?;
+
+    for city in &report.route {
+        writeln!(writer, "{}", city + 1)?;
+    }
+
+    writeln!(writer, "-1")?;
+    writeln!(writer, "EOF")
+}
+
+fn join_route(route: &[usize]) -> String {
+    route
+        .iter()
+        .map(|city| city.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::tsplib::TsplibFile;
+
+    fn report() -> SolutionReport {
+        let stats = RunStats {
+            generations: 12,
+            elapsed_secs: 0.5,
+            adaptation: vec![],
+            operators: vec![],
+        };
+
+        SolutionReport::new("square", vec![0, 2, 1, 3], 110, 100, stats)
+    }
+
+    fn written(format: OutputFormat) -> String {
+        let mut output = vec![];
+        write_solution(&mut output, &report(), format).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn tour_output_reads_back() {
+        let tour = written(OutputFormat::Tour);
+
+        assert!(tour.contains("TOUR_SECTION\n1\n3\n2\n4\n-1\nEOF"));
+
+        let file = TsplibFile::parse(&tour).unwrap();
+
+        assert_eq!(vec![vec![0, 2, 1, 3]], file.get_tours().unwrap());
+    }
+
+    #[test]
+    fn json_and_csv_contain_run_stats() {
+        let json: serde_json::Value = serde_json::from_str(&written(OutputFormat::Json)).unwrap();
+
+        assert_eq!(110, json["length"]);
+        assert_eq!(10.0, json["gap"]);
+        assert_eq!(12, json["stats"]["generations"]);
+
+        let csv = written(OutputFormat::Csv);
+
+        assert_eq!(
+            "name,length,gap,generations,elapsed_secs,route\nsquare,110,10.0000,12,0.500,0 2 1 3\n",
+            csv
+        );
+
+        assert_eq!(Ok(OutputFormat::Json), "JSON".parse());
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
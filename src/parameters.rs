@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+use crate::genetic::{Crossover, Genetic, Mutation, OperatorSelection, Replacement};
+use crate::problem::PermutationProblem;
+
+const MIN_ITERATIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Parameters {
+    pub iterations: usize,
+    pub population_size: usize,
+    pub elites_count: usize,
+    pub pair_count: usize,
+    pub crossing_prob: f64,
+    pub mutation_prob: f64,
+    pub stagnation_iter: usize,
+    pub max_mutation_multiply: usize,
+    pub threads: usize,
+    pub memetic_fraction: f64,
+    pub crossover: Crossover,
+    pub mutation: Mutation,
+    #[serde(default)]
+    pub self_adaptive: bool,
+    #[serde(default)]
+    pub operator_selection: OperatorSelection,
+    #[serde(default)]
+    pub replacement: Replacement,
+}
+
+impl Parameters {
+    pub fn for_problem<P: PermutationProblem>(problem: &P) -> Parameters {
+        let dimension = problem.dimension();
+
+        let iterations = if problem.is_symmetric() {
+            dimension
+        } else {
+            10 * dimension
+        };
+
+        Parameters {
+            iterations: 0,
+            population_size: 0,
+            elites_count: 0,
+            pair_count: 0,
+            crossing_prob: 0.9,
+            mutation_prob: 0.02,
+            stagnation_iter: 0,
+            max_mutation_multiply: 4,
+            threads: Genetic::default_threads(),
+            memetic_fraction: 0.1,
+            crossover: Crossover::Pmx,
+            mutation: Mutation::Auto,
+            self_adaptive: false,
+            operator_selection: OperatorSelection::Fixed,
+            replacement: Replacement::Generational,
+        }
+        .with_iterations(iterations.max(MIN_ITERATIONS))
+        .with_population_size(10 * dimension)
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self.stagnation_iter = iterations / 5;
+        self
+    }
+
+    pub fn with_population_size(mut self, population_size: usize) -> Self {
+        self.population_size = population_size;
+        self.elites_count = (population_size / 2).max(1);
+        self.pair_count = population_size / 2;
+        self
+    }
+
+    pub fn to_genetic(&self, opt: u32) -> Genetic {
+        Genetic::new(
+            opt,
+            self.iterations,
+            self.population_size,
+            self.elites_count,
+            self.pair_count,
+            self.crossing_prob,
+            self.mutation_prob,
+            self.stagnation_iter,
+            self.max_mutation_multiply,
+            self.threads,
+            self.memetic_fraction,
+        )
+        .with_operators(self.crossover, self.mutation)
+        .with_self_adaptation(self.self_adaptive)
+        .with_operator_selection(self.operator_selection)
+        .with_replacement(self.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::instance::Instance;
+    use crate::test_utils::line_instance;
+
+    #[test]
+    fn defaults_follow_symmetry_not_file_name() {
+        let symmetric = Parameters::for_problem(&line_instance(200));
+
+        assert_eq!(200, symmetric.iterations);
+        assert_eq!(40, symmetric.stagnation_iter);
+        assert_eq!(2000, symmetric.population_size);
+        assert_eq!(1000, symmetric.elites_count);
+
+        let matrix = (0..20)
+            .map(|i| {
+                (0..20)
+                    .map(|j| if i == j { 0 } else { i * 20 + j })
+                    .collect()
+            })
+            .collect();
+
+        let asymmetric = Parameters::for_problem(&Instance::from_matrix(matrix).unwrap());
+
+        assert_eq!(200, asymmetric.iterations);
+        assert_eq!(
+            MIN_ITERATIONS,
+            Parameters::for_problem(&line_instance(20)).iterations
+        );
+
+        let asymmetric = asymmetric.with_population_size(1);
+
+        assert_eq!(1, asymmetric.elites_count);
+        assert_eq!(0, asymmetric.pair_count);
+    }
+}
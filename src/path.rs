@@ -0,0 +1,287 @@
+use std::ops::Range;
+
+use crate::genetic::{Genetic, GeneticError};
+use crate::instance::Instance;
+use crate::problem::PermutationProblem;
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    Open,
+    FixedStart(usize),
+    FixedEnds(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct PathInstance {
+    instance: Instance,
+    mode: PathMode,
+}
+
+impl PathInstance {
+    pub fn new(instance: Instance, mode: PathMode) -> Result<PathInstance, GeneticError> {
+        let dimension = instance.dimension();
+
+        match mode {
+            PathMode::Open => {}
+            PathMode::FixedStart(start) => {
+                if start >= dimension {
+                    return Err(GeneticError::InvalidInstance(
+                        "start is not a node of the instance",
+                    ));
+                }
+            }
+            PathMode::FixedEnds(start, end) => {
+                if start >= dimension || end >= dimension {
+                    return Err(GeneticError::InvalidInstance(
+                        "endpoint is not a node of the instance",
+                    ));
+                }
+
+                if start == end {
+                    return Err(GeneticError::InvalidInstance(
+                        "path has to start and end in different nodes",
+                    ));
+                }
+            }
+        }
+
+        Ok(PathInstance { instance, mode })
+    }
+
+    pub fn from_file(path: &str, mode: PathMode) -> Result<PathInstance, GeneticError> {
+        let file = TsplibFile::from_file(path)?;
+
+        PathInstance::new(file.to_instance()?, mode)
+    }
+
+    pub fn get_mode(&self) -> PathMode {
+        self.mode
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<Vec<usize>, GeneticError> {
+        genetic.try_solve(self)
+    }
+
+    fn has_pinned_endpoints(&self, route: &[usize]) -> bool {
+        match self.mode {
+            PathMode::Open => true,
+            PathMode::FixedStart(start) => route.first() == Some(&start),
+            PathMode::FixedEnds(start, end) => {
+                route.first() == Some(&start) && route.last() == Some(&end)
+            }
+        }
+    }
+
+    fn edge_len(&self, route: &[usize], position: usize) -> u32 {
+        self.instance
+            .get_distance(route[position], route[position + 1])
+    }
+}
+
+impl PermutationProblem for PathInstance {
+    fn dimension(&self) -> usize {
+        self.instance.dimension()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.instance.is_symmetric()
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        let dimension = self.instance.dimension();
+
+        if route.len() != dimension || !self.has_pinned_endpoints(route) {
+            return None;
+        }
+
+        let mut visited = vec![false; dimension];
+
+        for &city in route {
+            if city >= dimension || visited[city] {
+                return None;
+            }
+
+            visited[city] = true;
+        }
+
+        (0..dimension.saturating_sub(1)).try_fold(0u32, |route_len, i| {
+            route_len.checked_add(self.edge_len(route, i))
+        })
+    }
+
+    fn movable_range(&self) -> Range<usize> {
+        let dimension = self.instance.dimension();
+
+        match self.mode {
+            PathMode::Open => 0..dimension,
+            PathMode::FixedStart(_) => 1.min(dimension)..dimension,
+            PathMode::FixedEnds(_, _) => 1.min(dimension)..dimension.saturating_sub(1),
+        }
+    }
+
+    fn repair(&self, route: &mut [usize]) {
+        let pin = |route: &mut [usize], city: usize, position: usize| {
+            if let Some(current) = route.iter().position(|&c| c == city) {
+                route.swap(current, position);
+            }
+        };
+
+        match self.mode {
+            PathMode::Open => {}
+            PathMode::FixedStart(start) => pin(route, start, 0),
+            PathMode::FixedEnds(start, end) => {
+                pin(route, start, 0);
+
+                if let Some(last) = route.len().checked_sub(1) {
+                    pin(route, end, last);
+                }
+            }
+        }
+    }
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        if !self.instance.is_symmetric() {
+            let mut route = route.to_vec();
+
+            route[i..=j].reverse();
+
+            return self.evaluate(&route).unwrap_or(u32::MAX) as i64 - route_len as i64;
+        }
+
+        let mut delta = 0;
+
+        if i > 0 {
+            delta += self.instance.get_distance(route[i - 1], route[j]) as i64;
+            delta -= self.edge_len(route, i - 1) as i64;
+        }
+
+        if j + 1 < route.len() {
+            delta += self.instance.get_distance(route[i], route[j + 1]) as i64;
+            delta -= self.edge_len(route, j) as i64;
+        }
+
+        delta
+    }
+
+    fn swap_delta(&self, route: &[usize], _route_len: u32, i: usize, j: usize) -> i64 {
+        let city_at = |position: usize| {
+            if position == i {
+                route[j]
+            } else if position == j {
+                route[i]
+            } else {
+                route[position]
+            }
+        };
+
+        let mut edges = vec![i.checked_sub(1), Some(i), j.checked_sub(1), Some(j)]
+            .into_iter()
+            .flatten()
+            .filter(|&position| position + 1 < route.len())
+            .collect::<Vec<_>>();
+
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut delta = 0;
+
+        for position in edges {
+            delta += self
+                .instance
+                .get_distance(city_at(position), city_at(position + 1)) as i64;
+            delta -= self.edge_len(route, position) as i64;
+        }
+
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::genetic::GeneticRng;
+    use crate::instance::Metric;
+    use crate::test_utils::{line_instance, small_genetic};
+
+    use rand::prelude::*;
+
+    #[test]
+    fn open_path_evaluated_without_closing_edge() {
+        let open = PathInstance::new(line_instance(4), PathMode::Open).unwrap();
+
+        assert_eq!(Some(30), open.evaluate(&[0, 1, 2, 3]));
+        assert_eq!(Some(50), open.evaluate(&[1, 0, 3, 2]));
+
+        let fixed = PathInstance::new(line_instance(4), PathMode::FixedEnds(1, 2)).unwrap();
+
+        assert_eq!(None, fixed.evaluate(&[0, 1, 2, 3]));
+        assert_eq!(Some(50), fixed.evaluate(&[1, 0, 3, 2]));
+
+        let mut route = vec![3, 2, 0, 1];
+        fixed.repair(&mut route);
+
+        assert_eq!(vec![1, 3, 0, 2], route);
+
+        let costly = Instance::from_upper_triangular(vec![
+            vec![u32::MAX / 2 + 1, 1],
+            vec![u32::MAX / 2 + 1],
+        ])
+        .unwrap();
+
+        let open = PathInstance::new(costly, PathMode::Open).unwrap();
+
+        assert_eq!(None, open.evaluate(&[0, 1, 2]));
+        assert_eq!(Some(u32::MAX / 2 + 2), open.evaluate(&[2, 0, 1]));
+    }
+
+    #[test]
+    fn deltas_match_evaluation() {
+        let mut rng = GeneticRng::seed_from_u64(34);
+
+        let coordinates = (0..12)
+            .map(|_| (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0)))
+            .collect::<Vec<_>>();
+
+        let instance = Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean);
+        let open = PathInstance::new(instance, PathMode::Open).unwrap();
+
+        let mut route = (0..12).collect::<Vec<_>>();
+        route.shuffle(&mut rng);
+
+        let route_len = open.evaluate(&route).unwrap();
+
+        for i in 0..12 {
+            for j in i + 1..12 {
+                let mut inverted = route.clone();
+                inverted[i..=j].reverse();
+
+                let mut swapped = route.clone();
+                swapped.swap(i, j);
+
+                assert_eq!(
+                    open.evaluate(&inverted).unwrap() as i64 - route_len as i64,
+                    open.invert_delta(&route, route_len, i, j)
+                );
+                assert_eq!(
+                    open.evaluate(&swapped).unwrap() as i64 - route_len as i64,
+                    open.swap_delta(&route, route_len, i, j)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pinned_endpoints_preserved() {
+        let path = PathInstance::new(line_instance(10), PathMode::FixedEnds(4, 7)).unwrap();
+
+        let genetic = small_genetic();
+
+        let route = path.solve(&genetic).unwrap();
+
+        assert_eq!(4, route[0]);
+        assert_eq!(7, route[9]);
+        assert!(path.evaluate(&route).is_some());
+    }
+}
@@ -0,0 +1,410 @@
+use std::ops::Range;
+
+use crate::genetic::{Genetic, GeneticError, GeneticRng};
+use crate::instance::Instance;
+use crate::path::{PathInstance, PathMode};
+use crate::problem::{insert, PermutationProblem};
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone)]
+pub struct Precedence<P> {
+    problem: P,
+    constraints: Vec<(usize, usize)>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl<P: PermutationProblem> Precedence<P> {
+    pub fn new(
+        problem: P,
+        constraints: Vec<(usize, usize)>,
+    ) -> Result<Precedence<P>, GeneticError> {
+        let dimension = problem.dimension();
+
+        let mut successors = vec![vec![]; dimension];
+        let mut indegree = vec![0; dimension];
+
+        for &(before, after) in &constraints {
+            if before >= dimension || after >= dimension {
+                return Err(GeneticError::InvalidInstance(
+                    "constrained city is not a node of the instance",
+                ));
+            }
+
+            if before == after {
+                return Err(GeneticError::InvalidInstance(
+                    "city cannot have to precede itself",
+                ));
+            }
+
+            successors[before].push(after);
+            indegree[after] += 1;
+        }
+
+        let mut pinned_route = (0..dimension).collect::<Vec<_>>();
+        problem.repair(&mut pinned_route);
+
+        let movable = problem.movable_range();
+        let leading = &pinned_route[..movable.start];
+        let trailing = &pinned_route[movable.end..];
+
+        for &(before, after) in &constraints {
+            if leading.contains(&after) || trailing.contains(&before) {
+                return Err(GeneticError::InvalidConfiguration(
+                    "precedence constraint contradicts a fixed endpoint",
+                ));
+            }
+        }
+
+        let mut ready = (0..dimension)
+            .filter(|&city| indegree[city] == 0)
+            .collect::<Vec<_>>();
+
+        let mut ordered = 0;
+
+        while let Some(city) = ready.pop() {
+            ordered += 1;
+
+            for &successor in &successors[city] {
+                indegree[successor] -= 1;
+
+                if indegree[successor] == 0 {
+                    ready.push(successor);
+                }
+            }
+        }
+
+        if ordered != dimension {
+            return Err(GeneticError::InvalidInstance(
+                "precedence constraints contain a cycle",
+            ));
+        }
+
+        Ok(Precedence {
+            problem,
+            constraints,
+            successors,
+        })
+    }
+
+    pub fn get_problem(&self) -> &P {
+        &self.problem
+    }
+
+    pub fn get_constraints(&self) -> &[(usize, usize)] {
+        &self.constraints
+    }
+
+    pub fn is_feasible(&self, route: &[usize]) -> bool {
+        let dimension = self.problem.dimension();
+
+        if route.len() != dimension || route.iter().any(|&city| city >= dimension) {
+            return false;
+        }
+
+        let mut positions = vec![0; dimension];
+
+        for (position, &city) in route.iter().enumerate() {
+            positions[city] = position;
+        }
+
+        self.constraints
+            .iter()
+            .all(|&(before, after)| positions[before] < positions[after])
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<Vec<usize>, GeneticError> {
+        genetic.try_solve(self)
+    }
+
+    fn feasible_delta(&self, moved_route: &[usize], delta: i64) -> i64 {
+        if self.is_feasible(moved_route) {
+            delta
+        } else {
+            i64::MAX
+        }
+    }
+}
+
+impl Precedence<PathInstance> {
+    pub fn from_sop_file(path: &str) -> Result<Precedence<PathInstance>, GeneticError> {
+        let file = TsplibFile::from_file(path)?;
+
+        Precedence::from_sop_tsplib(&file)
+    }
+
+    pub fn from_sop_tsplib(file: &TsplibFile) -> Result<Precedence<PathInstance>, GeneticError> {
+        let dimension = file.get_number::<usize>("DIMENSION")?;
+
+        let mut weights = file
+            .get_numbers::<i64>("EDGE_WEIGHT_SECTION")?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if weights.len() == dimension * dimension + 1 {
+            weights.remove(0);
+        }
+
+        if weights.len() != dimension * dimension {
+            return Err(GeneticError::InvalidFile(format!(
+                "expected {} edge weights, found {}",
+                dimension * dimension,
+                weights.len()
+            )));
+        }
+
+        let mut matrix = vec![vec![0; dimension]; dimension];
+        let mut constraints = vec![];
+
+        for i in 0..dimension {
+            for j in 0..dimension {
+                match weights[i * dimension + j] {
+                    -1 => constraints.push((j, i)),
+                    weight if weight >= 0 && weight <= u32::MAX as i64 => {
+                        matrix[i][j] = weight as u32
+                    }
+                    weight => {
+                        return Err(GeneticError::InvalidFile(format!(
+                            "invalid edge weight: {}",
+                            weight
+                        )))
+                    }
+                }
+            }
+        }
+
+        let mode = PathMode::FixedEnds(0, dimension.saturating_sub(1));
+        let path = PathInstance::new(Instance::from_matrix(matrix)?, mode)?;
+
+        Precedence::new(path, constraints)
+    }
+}
+
+impl<P: PermutationProblem> PermutationProblem for Precedence<P> {
+    fn dimension(&self) -> usize {
+        self.problem.dimension()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.problem.is_symmetric()
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        if self.is_feasible(route) {
+            self.problem.evaluate(route)
+        } else {
+            None
+        }
+    }
+
+    fn movable_range(&self) -> Range<usize> {
+        self.problem.movable_range()
+    }
+
+    fn mutate(&self, route: &mut [usize], mutation_prob: f64, rng: &mut GeneticRng) {
+        self.problem.mutate(route, mutation_prob, rng);
+    }
+
+    fn repair(&self, route: &mut [usize]) {
+        self.problem.repair(route);
+
+        let dimension = self.problem.dimension();
+
+        if route.iter().any(|&city| city >= dimension) {
+            return;
+        }
+
+        let movable = self.problem.movable_range();
+        let segment = &mut route[movable];
+
+        let mut in_segment = vec![false; dimension];
+
+        for &city in segment.iter() {
+            in_segment[city] = true;
+        }
+
+        let mut indegree = vec![0; dimension];
+
+        for &(before, after) in &self.constraints {
+            if in_segment[before] && in_segment[after] {
+                indegree[after] += 1;
+            }
+        }
+
+        let mut pending = segment.to_vec();
+
+        for slot in segment.iter_mut() {
+            let position = match pending.iter().position(|&city| indegree[city] == 0) {
+                Some(position) => position,
+                None => return,
+            };
+
+            let city = pending.remove(position);
+
+            for &successor in &self.successors[city] {
+                if in_segment[successor] {
+                    indegree[successor] -= 1;
+                }
+            }
+
+            *slot = city;
+        }
+    }
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut moved_route = route.to_vec();
+
+        moved_route[i..=j].reverse();
+
+        self.feasible_delta(
+            &moved_route,
+            self.problem.invert_delta(route, route_len, i, j),
+        )
+    }
+
+    fn swap_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut moved_route = route.to_vec();
+
+        moved_route.swap(i, j);
+
+        self.feasible_delta(
+            &moved_route,
+            self.problem.swap_delta(route, route_len, i, j),
+        )
+    }
+
+    fn insert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut moved_route = route.to_vec();
+
+        insert(&mut moved_route, i, j);
+
+        self.feasible_delta(
+            &moved_route,
+            self.problem.insert_delta(route, route_len, i, j),
+        )
+    }
+}
+
+pub fn read_constraints(path: &str) -> Result<Vec<(usize, usize)>, GeneticError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| GeneticError::InvalidFile(format!("{}: {}", path, err)))?;
+
+    parse_constraints(&text)
+}
+
+pub fn parse_constraints(text: &str) -> Result<Vec<(usize, usize)>, GeneticError> {
+    let mut constraints = vec![];
+
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let nodes = line
+            .split_whitespace()
+            .map(|token| token.parse::<usize>().ok().filter(|&node| node >= 1))
+            .collect::<Option<Vec<_>>>();
+
+        match nodes.as_deref() {
+            Some(&[before, after]) => constraints.push((before - 1, after - 1)),
+            _ => {
+                return Err(GeneticError::InvalidFile(format!(
+                    "constraint lines need two nodes, found: {}",
+                    line
+                )))
+            }
+        }
+    }
+
+    Ok(constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_utils::{line_instance, small_genetic};
+
+    fn line_path(dimension: usize) -> PathInstance {
+        PathInstance::new(line_instance(dimension), PathMode::Open).unwrap()
+    }
+
+    #[test]
+    fn constraints_parsed_and_repaired() {
+        let constraints =
+            parse_constraints("# pickup before delivery\n4 1\n\n3 2 # second pair\n").unwrap();
+
+        assert_eq!(vec![(3, 0), (2, 1)], constraints);
+        assert!(parse_constraints("1 2 3").is_err());
+
+        let precedence = Precedence::new(line_path(5), constraints).unwrap();
+
+        let mut route = vec![0, 1, 2, 3, 4];
+        precedence.repair(&mut route);
+
+        assert_eq!(vec![2, 1, 3, 0, 4], route);
+        assert!(precedence.is_feasible(&route));
+        assert_eq!(None, precedence.evaluate(&[0, 1, 2, 3, 4]));
+        assert_eq!(i64::MAX, precedence.swap_delta(&route, 40, 0, 1));
+
+        assert!(Precedence::new(line_path(3), vec![(0, 1), (1, 2), (2, 0)]).is_err());
+    }
+
+    #[test]
+    fn constraints_against_fixed_endpoints_rejected() {
+        let path = |mode| PathInstance::new(line_instance(5), mode).unwrap();
+
+        for (mode, constraints) in [
+            (PathMode::FixedStart(2), vec![(0, 2)]),
+            (PathMode::FixedEnds(2, 4), vec![(1, 2)]),
+            (PathMode::FixedEnds(2, 4), vec![(4, 3)]),
+        ] {
+            assert!(matches!(
+                Precedence::new(path(mode), constraints),
+                Err(GeneticError::InvalidConfiguration(_))
+            ));
+        }
+
+        assert!(Precedence::new(path(PathMode::FixedEnds(2, 4)), vec![(2, 0), (1, 4)]).is_ok());
+    }
+
+    #[test]
+    fn sop_file_parsed_correctly() {
+        let file = TsplibFile::parse(
+            "NAME : tiny
+TYPE : SOP
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : FULL_MATRIX
+EDGE_WEIGHT_SECTION
+4
+0 5 1 9
+-1 0 3 2
+-1 -1 0 4
+-1 -1 -1 0
+EOF",
+        )
+        .unwrap();
+
+        let sop = Precedence::from_sop_tsplib(&file).unwrap();
+
+        assert_eq!(6, sop.get_constraints().len());
+        assert_eq!(Some(5 + 3 + 4), sop.evaluate(&[0, 1, 2, 3]));
+        assert_eq!(None, sop.evaluate(&[0, 2, 1, 3]));
+    }
+
+    #[test]
+    fn solved_route_respects_precedence() {
+        let constraints = vec![(9, 0), (7, 2), (5, 4), (8, 1)];
+
+        let precedence = Precedence::new(line_path(10), constraints).unwrap();
+
+        let genetic = small_genetic();
+
+        let route = precedence.solve(&genetic).unwrap();
+
+        assert!(precedence.is_feasible(&route));
+    }
+}
@@ -0,0 +1,88 @@
+use std::ops::Range;
+
+use tsp_parser::{neighbourhood, Tsp, TspType};
+
+use crate::genetic::GeneticRng;
+
+pub trait PermutationProblem: Sync {
+    fn dimension(&self) -> usize;
+
+    fn is_symmetric(&self) -> bool;
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32>;
+
+    fn movable_range(&self) -> Range<usize> {
+        0..self.dimension()
+    }
+
+    fn repair(&self, _route: &mut [usize]) {}
+
+    fn mutate(&self, _route: &mut [usize], _mutation_prob: f64, _rng: &mut GeneticRng) {}
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut route = route.to_vec();
+
+        neighbourhood::invert(&mut route[i..=j]);
+
+        delta(self.evaluate(&route), route_len)
+    }
+
+    fn swap_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut route = route.to_vec();
+
+        neighbourhood::swap(&mut route[i..=j]);
+
+        delta(self.evaluate(&route), route_len)
+    }
+
+    fn insert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        let mut route = route.to_vec();
+
+        insert(&mut route, i, j);
+
+        delta(self.evaluate(&route), route_len)
+    }
+}
+
+pub trait MultiObjectiveProblem: PermutationProblem {
+    fn objectives_count(&self) -> usize;
+
+    fn evaluate_objectives(&self, route: &[usize]) -> Option<Vec<u32>>;
+}
+
+fn delta(new_route_len: Option<u32>, route_len: u32) -> i64 {
+    match new_route_len {
+        Some(new_route_len) => new_route_len as i64 - route_len as i64,
+        None => i64::MAX,
+    }
+}
+
+pub fn insert(route: &mut [usize], from: usize, to: usize) {
+    if from < to {
+        route[from..=to].rotate_left(1);
+    } else {
+        route[to..=from].rotate_right(1);
+    }
+}
+
+impl PermutationProblem for Tsp {
+    fn dimension(&self) -> usize {
+        self.get_dimension()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        matches!(self.get_tsp_type(), TspType::Symmetric)
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        self.get_route_len(route)
+    }
+
+    fn invert_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        self.get_inverted_route_len(route, route_len, i, j) as i64 - route_len as i64
+    }
+
+    fn swap_delta(&self, route: &[usize], route_len: u32, i: usize, j: usize) -> i64 {
+        self.get_swap_route_len(route, route_len, i, j) as i64 - route_len as i64
+    }
+}
@@ -0,0 +1,14 @@
+use crate::genetic::Genetic;
+use crate::instance::{Instance, Metric};
+
+pub fn line_instance(dimension: usize) -> Instance {
+    let coordinates = (0..dimension)
+        .map(|i| (10.0 * i as f64, 0.0))
+        .collect::<Vec<_>>();
+
+    Instance::from_coordinates(&coordinates, Metric::RoundedEuclidean)
+}
+
+pub fn small_genetic() -> Genetic {
+    Genetic::new(0, 50, 10, 3, 5, 0.9, 0.2, 10, 4, 2, 0.5)
+}
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use crate::genetic::GeneticError;
+use crate::instance::{Instance, Metric};
+
+pub struct TsplibFile {
+    specification: HashMap<String, String>,
+    sections: HashMap<String, Vec<Vec<String>>>,
+}
+
+impl TsplibFile {
+    pub fn from_file(path: &str) -> Result<TsplibFile, GeneticError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| GeneticError::InvalidFile(format!("{}: {}", path, err)))?;
+
+        TsplibFile::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<TsplibFile, GeneticError> {
+        let mut specification = HashMap::new();
+        let mut sections: HashMap<String, Vec<Vec<String>>> = HashMap::new();
+
+        let mut curr_section = None;
+
+        for line in text.lines() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line == "EOF" {
+                break;
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                let key = key.trim();
+
+                if is_keyword(key) {
+                    specification.insert(key.to_string(), value.trim().to_string());
+                    curr_section = None;
+
+                    continue;
+                }
+            }
+
+            let first_token = line.split_whitespace().next().unwrap_or_default();
+
+            if first_token.ends_with("_SECTION") {
+                sections.entry(first_token.to_string()).or_default();
+                curr_section = Some(first_token.to_string());
+
+                continue;
+            }
+
+            match &curr_section {
+                Some(section) => sections
+                    .entry(section.clone())
+                    .or_default()
+                    .push(line.split_whitespace().map(str::to_string).collect()),
+                None => {
+                    return Err(GeneticError::InvalidFile(format!(
+                        "unexpected line outside of any section: {}",
+                        line
+                    )))
+                }
+            }
+        }
+
+        Ok(TsplibFile {
+            specification,
+            sections,
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.specification.get(key).map(String::as_str)
+    }
+
+    pub fn get_number<T: std::str::FromStr>(&self, key: &str) -> Result<T, GeneticError> {
+        let value = self
+            .get(key)
+            .ok_or_else(|| GeneticError::InvalidFile(format!("missing {}", key)))?;
+
+        value
+            .parse()
+            .map_err(|_| GeneticError::InvalidFile(format!("invalid {}: {}", key, value)))
+    }
+
+    pub fn get_section(&self, name: &str) -> Option<&Vec<Vec<String>>> {
+        self.sections.get(name)
+    }
+
+    pub fn get_numbers<T: std::str::FromStr>(
+        &self,
+        name: &str,
+    ) -> Result<Vec<Vec<T>>, GeneticError> {
+        let section = self
+            .get_section(name)
+            .ok_or_else(|| GeneticError::InvalidFile(format!("missing {}", name)))?;
+
+        let mut rows = Vec::with_capacity(section.len());
+
+        for line in section {
+            let mut row = Vec::with_capacity(line.len());
+
+            for token in line {
+                let number = token
+                    .parse()
+                    .map_err(|_| GeneticError::InvalidFile(format!("invalid number: {}", token)))?;
+
+                row.push(number);
+            }
+
+            rows.push(row);
+        }
+
+        Ok(rows)
+    }
+
+    pub fn get_node_rows<T: std::str::FromStr>(
+        &self,
+        name: &str,
+    ) -> Result<Vec<(usize, Vec<T>)>, GeneticError> {
+        let dimension = self.get_number::<usize>("DIMENSION")?;
+
+        let mut rows = vec![];
+
+        for mut row in self.get_numbers::<String>(name)? {
+            let node = match row.first().and_then(|node| node.parse::<usize>().ok()) {
+                Some(node) if node >= 1 && node <= dimension => node - 1,
+                _ => {
+                    return Err(GeneticError::InvalidFile(format!(
+                        "invalid node in {}",
+                        name
+                    )))
+                }
+            };
+
+            let mut values = Vec::with_capacity(row.len() - 1);
+
+            for token in row.drain(1..) {
+                let value = token
+                    .parse()
+                    .map_err(|_| GeneticError::InvalidFile(format!("invalid number: {}", token)))?;
+
+                values.push(value);
+            }
+
+            rows.push((node, values));
+        }
+
+        Ok(rows)
+    }
+
+    pub fn get_node_values<T: std::str::FromStr + Clone>(
+        &self,
+        name: &str,
+        default: T,
+    ) -> Result<Vec<T>, GeneticError> {
+        let dimension = self.get_number::<usize>("DIMENSION")?;
+
+        let mut values = vec![default; dimension];
+
+        for (node, row) in self.get_node_rows::<T>(name)? {
+            match &row[..] {
+                [value] => values[node] = value.clone(),
+                _ => {
+                    return Err(GeneticError::InvalidFile(format!(
+                        "{} lines need a node and its value",
+                        name
+                    )))
+                }
+            }
+        }
+
+        Ok(values)
+    }
+
+    pub fn get_depot(&self) -> Result<usize, GeneticError> {
+        let section = match self.get_section("DEPOT_SECTION") {
+            Some(section) => section,
+            None => return Ok(0),
+        };
+
+        let dimension = self.get_number::<usize>("DIMENSION")?;
+
+        section
+            .iter()
+            .flatten()
+            .next()
+            .and_then(|token| token.parse::<usize>().ok())
+            .filter(|&node| node >= 1 && node <= dimension)
+            .map(|node| node - 1)
+            .ok_or_else(|| GeneticError::InvalidFile("invalid DEPOT_SECTION".to_string()))
+    }
+
+    pub fn get_tours(&self) -> Result<Vec<Vec<usize>>, GeneticError> {
+        let mut tours = vec![];
+        let mut tour = vec![];
+
+        for node in self
+            .get_numbers::<i64>("TOUR_SECTION")?
+            .into_iter()
+            .flatten()
+        {
+            match node {
+                -1 => tours.push(std::mem::take(&mut tour)),
+                node if node >= 1 => tour.push(node as usize - 1),
+                node => return Err(GeneticError::InvalidFile(format!("invalid node {}", node))),
+            }
+        }
+
+        if !tour.is_empty() {
+            tours.push(tour);
+        }
+
+        Ok(tours)
+    }
+
+    pub fn to_instance(&self) -> Result<Instance, GeneticError> {
+        let dimension = self.get_number::<usize>("DIMENSION")?;
+        let edge_weight_type = self.get("EDGE_WEIGHT_TYPE").unwrap_or("EUC_2D");
+
+        let metric = match edge_weight_type {
+            "EUC_2D" => Metric::RoundedEuclidean,
+            "GEO" => Metric::Geographic,
+            "MAN_2D" => Metric::Manhattan,
+            "MAX_2D" => Metric::Chebyshev,
+            "EXPLICIT" => return self.explicit_instance(dimension),
+            other => {
+                return Err(GeneticError::InvalidFile(format!(
+                    "unsupported EDGE_WEIGHT_TYPE: {}",
+                    other
+                )))
+            }
+        };
+
+        let mut coordinates = vec![(0.0, 0.0); dimension];
+
+        for row in self.get_numbers::<f64>("NODE_COORD_SECTION")? {
+            if row.len() < 3 {
+                return Err(GeneticError::InvalidFile(
+                    "node coordinates need index, x and y".to_string(),
+                ));
+            }
+
+            let index = row[0] as usize;
+
+            if index == 0 || index > dimension {
+                return Err(GeneticError::InvalidFile(format!("invalid node {}", index)));
+            }
+
+            coordinates[index - 1] = (row[1], row[2]);
+        }
+
+        Ok(Instance::from_coordinates(&coordinates, metric))
+    }
+
+    fn explicit_instance(&self, dimension: usize) -> Result<Instance, GeneticError> {
+        let weights = self
+            .get_numbers::<u32>("EDGE_WEIGHT_SECTION")?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let format = self.get("EDGE_WEIGHT_FORMAT").unwrap_or("FULL_MATRIX");
+
+        let cells = match format {
+            "FULL_MATRIX" => (0..dimension)
+                .flat_map(|i| (0..dimension).map(move |j| (i, j)))
+                .collect::<Vec<_>>(),
+            "UPPER_ROW" => (0..dimension)
+                .flat_map(|i| (i + 1..dimension).map(move |j| (i, j)))
+                .collect(),
+            "UPPER_DIAG_ROW" => (0..dimension)
+                .flat_map(|i| (i..dimension).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_ROW" => (0..dimension)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .collect(),
+            "LOWER_DIAG_ROW" => (0..dimension)
+                .flat_map(|i| (0..=i).map(move |j| (i, j)))
+                .collect(),
+            other => {
+                return Err(GeneticError::InvalidFile(format!(
+                    "unsupported EDGE_WEIGHT_FORMAT: {}",
+                    other
+                )))
+            }
+        };
+
+        if weights.len() != cells.len() {
+            return Err(GeneticError::InvalidFile(format!(
+                "expected {} edge weights, found {}",
+                cells.len(),
+                weights.len()
+            )));
+        }
+
+        let mut matrix = vec![vec![0; dimension]; dimension];
+
+        for ((i, j), weight) in cells.into_iter().zip(weights) {
+            matrix[i][j] = weight;
+
+            if format != "FULL_MATRIX" {
+                matrix[j][i] = weight;
+            }
+        }
+
+        Instance::from_matrix(matrix)
+    }
+}
+
+pub fn read_tours(path: &str) -> Result<Vec<Vec<usize>>, GeneticError> {
+    TsplibFile::from_file(path)?.get_tours()
+}
+
+fn is_keyword(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::problem::PermutationProblem;
+
+    #[test]
+    fn explicit_file_parsed_correctly() {
+        let file = TsplibFile::parse(
+            "NAME : test
+TYPE : TSP
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+1 2 3
+4 5
+6
+EOF",
+        )
+        .unwrap();
+
+        let instance = file.to_instance().unwrap();
+
+        assert_eq!(Some("test"), file.get("NAME"));
+        assert_eq!(4, instance.dimension());
+        assert_eq!(5, instance.get_distance(3, 1));
+        assert_eq!(Some(1 + 4 + 6 + 3), instance.evaluate(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn coordinates_file_parsed_correctly() {
+        let file = TsplibFile::parse(
+            "NAME: square
+DIMENSION: 4
+EDGE_WEIGHT_TYPE: EUC_2D
+NODE_COORD_SECTION
+1 0 0
+2 3 0
+3 3 4
+4 0 4
+EOF",
+        )
+        .unwrap();
+
+        let instance = file.to_instance().unwrap();
+
+        assert_eq!(Some(14), instance.evaluate(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn node_sections_parsed_correctly() {
+        let file = TsplibFile::parse(
+            "NAME : tiny
+DIMENSION : 3
+DEMAND_SECTION
+1 0
+3 7
+WINDOW_SECTION
+2 5 9
+DEPOT_SECTION
+2
+-1
+EOF",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![0, 1, 7],
+            file.get_node_values("DEMAND_SECTION", 1).unwrap()
+        );
+        assert_eq!(
+            vec![(1, vec![5, 9])],
+            file.get_node_rows::<u32>("WINDOW_SECTION").unwrap()
+        );
+        assert!(file.get_node_values::<u32>("WINDOW_SECTION", 0).is_err());
+        assert_eq!(1, file.get_depot().unwrap());
+
+        let file = TsplibFile::parse("DIMENSION : 2\nDEMAND_SECTION\n3 1\n").unwrap();
+
+        assert!(file.get_node_values::<u32>("DEMAND_SECTION", 0).is_err());
+        assert_eq!(0, file.get_depot().unwrap());
+
+        let file = TsplibFile::parse("DIMENSION : 2\nDEPOT_SECTION\n3\n-1\n").unwrap();
+
+        assert!(matches!(
+            file.get_depot(),
+            Err(GeneticError::InvalidFile(_))
+        ));
+    }
+
+    #[test]
+    fn tour_file_parsed_correctly() {
+        let file = TsplibFile::parse(
+            "NAME : square.tour
+TYPE : TOUR
+DIMENSION : 4
+TOUR_SECTION
+1
+3 2
+4
+-1
+EOF",
+        )
+        .unwrap();
+
+        assert_eq!(vec![vec![0, 2, 1, 3]], file.get_tours().unwrap());
+    }
+}
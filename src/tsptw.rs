@@ -0,0 +1,383 @@
+use crate::genetic::{Genetic, GeneticError};
+use crate::instance::Instance;
+use crate::problem::PermutationProblem;
+use crate::tsplib::TsplibFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+    pub earliest: u32,
+    pub latest: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TsptwWeights {
+    pub travel_weight: u32,
+    pub violation_weight: u32,
+    pub lexicographic: bool,
+}
+
+impl Default for TsptwWeights {
+    fn default() -> Self {
+        TsptwWeights {
+            travel_weight: 1,
+            violation_weight: 100,
+            lexicographic: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub route: Vec<usize>,
+    pub arrivals: Vec<u32>,
+    pub travel_time: u32,
+    pub violation: u32,
+    pub fitness: u32,
+}
+
+impl Schedule {
+    pub fn is_feasible(&self) -> bool {
+        self.violation == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TsptwInstance {
+    instance: Instance,
+    windows: Vec<TimeWindow>,
+    service_times: Vec<u32>,
+    depot: usize,
+    weights: TsptwWeights,
+    travel_bound: u64,
+    exact_violations: u64,
+}
+
+impl TsptwInstance {
+    pub fn new(
+        instance: Instance,
+        windows: Vec<TimeWindow>,
+        service_times: Vec<u32>,
+        depot: usize,
+        weights: TsptwWeights,
+    ) -> Result<TsptwInstance, GeneticError> {
+        let dimension = instance.dimension();
+
+        if depot >= dimension {
+            return Err(GeneticError::InvalidInstance(
+                "depot is not a node of the instance",
+            ));
+        }
+
+        if windows.len() != dimension || service_times.len() != dimension {
+            return Err(GeneticError::InvalidInstance(
+                "every node needs a time window and a service time",
+            ));
+        }
+
+        if windows.iter().any(|window| window.earliest > window.latest) {
+            return Err(GeneticError::InvalidInstance(
+                "time window has to open before it closes",
+            ));
+        }
+
+        let travel_bound = (0..dimension)
+            .map(|from| {
+                (0..dimension)
+                    .map(|to| instance.get_distance(from, to) as u64)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum::<u64>();
+
+        let exact_violations = ((u32::MAX as u64 / 2) / (travel_bound + 1)).max(1);
+
+        Ok(TsptwInstance {
+            instance,
+            windows,
+            service_times,
+            depot,
+            weights,
+            travel_bound,
+            exact_violations,
+        })
+    }
+
+    pub fn from_file(path: &str, weights: TsptwWeights) -> Result<TsptwInstance, GeneticError> {
+        let file = TsplibFile::from_file(path)?;
+
+        TsptwInstance::from_tsplib(&file, weights)
+    }
+
+    pub fn from_tsplib(
+        file: &TsplibFile,
+        weights: TsptwWeights,
+    ) -> Result<TsptwInstance, GeneticError> {
+        let instance = file.to_instance()?;
+        let dimension = instance.dimension();
+
+        let mut windows = vec![
+            TimeWindow {
+                earliest: 0,
+                latest: u32::MAX,
+            };
+            dimension
+        ];
+
+        for (node, row) in file.get_node_rows::<u32>("TIME_WINDOW_SECTION")? {
+            match row[..] {
+                [earliest, latest] => windows[node] = TimeWindow { earliest, latest },
+                _ => {
+                    return Err(GeneticError::InvalidFile(
+                        "time window lines need a node, its earliest and latest time".to_string(),
+                    ))
+                }
+            }
+        }
+
+        let service_times = if file.get_section("SERVICE_TIME_SECTION").is_some() {
+            file.get_node_values("SERVICE_TIME_SECTION", 0)?
+        } else {
+            vec![0; dimension]
+        };
+
+        let depot = file.get_depot()?;
+
+        TsptwInstance::new(instance, windows, service_times, depot, weights)
+    }
+
+    pub fn get_weights(&self) -> TsptwWeights {
+        self.weights
+    }
+
+    pub fn solve(&self, genetic: &Genetic) -> Result<Schedule, GeneticError> {
+        let route = genetic.try_solve(self)?;
+
+        self.schedule(&route).ok_or(GeneticError::InvalidRoute)
+    }
+
+    pub fn schedule(&self, route: &[usize]) -> Option<Schedule> {
+        let dimension = self.instance.dimension();
+
+        if route.len() != dimension {
+            return None;
+        }
+
+        let depot_position = route.iter().position(|&city| city == self.depot)?;
+
+        let route = route[depot_position..]
+            .iter()
+            .chain(&route[..depot_position])
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut visited = vec![false; dimension];
+        let mut arrivals = Vec::with_capacity(dimension + 1);
+
+        let mut time = self.windows[self.depot].earliest;
+        let mut travel_time = 0u32;
+        let mut violation = 0u32;
+
+        arrivals.push(time);
+
+        for i in 0..dimension {
+            let city = route[i];
+
+            if city >= dimension || visited[city] {
+                return None;
+            }
+
+            visited[city] = true;
+
+            let next_city = route[(i + 1) % dimension];
+            let distance = self.instance.get_distance(city, next_city);
+
+            travel_time = travel_time.saturating_add(distance);
+            time = time
+                .saturating_add(self.service_times[city])
+                .saturating_add(distance);
+
+            let window = self.windows[next_city];
+
+            if time < window.earliest && next_city != self.depot {
+                time = window.earliest;
+            }
+
+            if time > window.latest {
+                violation = violation.saturating_add(time - window.latest);
+            }
+
+            arrivals.push(time);
+        }
+
+        let fitness = self.fitness(travel_time, violation);
+
+        Some(Schedule {
+            route,
+            arrivals,
+            travel_time,
+            violation,
+            fitness,
+        })
+    }
+
+    fn fitness(&self, travel_time: u32, violation: u32) -> u32 {
+        let violation = violation as u64;
+
+        let fitness = if self.weights.lexicographic {
+            if violation < self.exact_violations {
+                violation * (self.travel_bound + 1) + travel_time as u64
+            } else {
+                self.exact_violations * (self.travel_bound + 1) + violation - self.exact_violations
+            }
+        } else {
+            self.weights.travel_weight as u64 * travel_time as u64
+                + self.weights.violation_weight as u64 * violation
+        };
+
+        fitness.min(u32::MAX as u64) as u32
+    }
+}
+
+impl PermutationProblem for TsptwInstance {
+    fn dimension(&self) -> usize {
+        self.instance.dimension()
+    }
+
+    fn is_symmetric(&self) -> bool {
+        false
+    }
+
+    fn evaluate(&self, route: &[usize]) -> Option<u32> {
+        self.schedule(route).map(|schedule| schedule.fitness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::prelude::*;
+
+    const TSPTW_FILE: &str = "NAME : line
+TYPE : TSPTW
+DIMENSION : 4
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : FULL_MATRIX
+EDGE_WEIGHT_SECTION
+0 10 20 30
+10 0 10 20
+20 10 0 10
+30 20 10 0
+TIME_WINDOW_SECTION
+1 0 1000
+2 0 15
+3 40 50
+4 0 100
+SERVICE_TIME_SECTION
+1 0
+2 5
+3 5
+4 5
+DEPOT_SECTION
+1
+-1
+EOF";
+
+    #[test]
+    fn schedule_computed_correctly() {
+        let file = TsplibFile::parse(TSPTW_FILE).unwrap();
+        let tsptw = TsptwInstance::from_tsplib(&file, TsptwWeights::default()).unwrap();
+
+        let schedule = tsptw.schedule(&[2, 3, 0, 1]).unwrap();
+
+        assert_eq!(vec![0, 1, 2, 3], schedule.route);
+        assert_eq!(vec![0, 10, 40, 55, 90], schedule.arrivals);
+        assert_eq!(60, schedule.travel_time);
+        assert!(schedule.is_feasible());
+        assert_eq!(60, schedule.fitness);
+
+        let schedule = tsptw.schedule(&[0, 2, 1, 3]).unwrap();
+
+        assert_eq!(vec![0, 40, 55, 80, 115], schedule.arrivals);
+        assert_eq!(40, schedule.violation);
+        assert_eq!(80 + 100 * 40, schedule.fitness);
+    }
+
+    #[test]
+    fn lexicographic_fitness_prefers_feasible_routes() {
+        let file = TsplibFile::parse(TSPTW_FILE).unwrap();
+
+        let weights = TsptwWeights {
+            travel_weight: 1,
+            violation_weight: 0,
+            lexicographic: true,
+        };
+
+        let tsptw = TsptwInstance::from_tsplib(&file, weights).unwrap();
+
+        let feasible = tsptw.evaluate(&[0, 1, 2, 3]).unwrap();
+        let infeasible = tsptw.evaluate(&[0, 2, 1, 3]).unwrap();
+
+        assert!(feasible < infeasible);
+        assert!(tsptw.fitness(90, 39) < tsptw.fitness(60, 40));
+
+        let genetic = Genetic::new(60, 50, 4, 2, 2, 0.9, 0.02, 10, 4, 1, 0.5);
+
+        let schedule = tsptw.solve(&genetic).unwrap();
+
+        assert!(schedule.is_feasible());
+    }
+
+    #[test]
+    fn lexicographic_fitness_keeps_large_instances_distinct() {
+        let mut rng = crate::genetic::GeneticRng::seed_from_u64(40);
+
+        let coordinates = (0..40)
+            .map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0)))
+            .collect::<Vec<_>>();
+
+        let instance = Instance::from_coordinates(&coordinates, crate::instance::Metric::Euclidean);
+
+        let windows = (0..40)
+            .map(|i| TimeWindow {
+                earliest: i * 500,
+                latest: i * 500 + 500,
+            })
+            .collect();
+
+        let weights = TsptwWeights {
+            travel_weight: 1,
+            violation_weight: 0,
+            lexicographic: true,
+        };
+
+        let tsptw = TsptwInstance::new(instance, windows, vec![10; 40], 0, weights).unwrap();
+
+        let mut schedules = (0..200)
+            .map(|_| {
+                let mut route = (0..40).collect::<Vec<_>>();
+                route.shuffle(&mut rng);
+
+                tsptw.schedule(&route).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        schedules.sort_by_key(|schedule| (schedule.violation, schedule.travel_time));
+
+        for pair in schedules.windows(2) {
+            assert!(pair[0].fitness <= pair[1].fitness);
+
+            if pair[0].violation < pair[1].violation {
+                assert!(pair[0].fitness < pair[1].fitness);
+            }
+        }
+
+        let exact_violations = tsptw.exact_violations as u32;
+        let travel_bound = tsptw.travel_bound as u32;
+
+        assert!(
+            tsptw.fitness(travel_bound, exact_violations - 1) < tsptw.fitness(0, exact_violations)
+        );
+        assert!(tsptw.fitness(0, 1 << 30) < tsptw.fitness(0, (1 << 30) + 1));
+    }
+}